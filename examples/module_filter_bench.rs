@@ -0,0 +1,65 @@
+//! Example: module filter throughput with 50 allow/deny entries.
+//!
+//! Compares a linear `starts_with` scan over the lists (how filters used to be
+//! checked) with a precompiled `ModuleFilter` (what the logger uses).
+//!
+//! Run with: cargo run --release --example module_filter_bench
+
+use nanologger::ModuleFilter;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: usize = 200_000;
+
+fn linear(module_path: &str, allow: &[String], deny: &[String]) -> bool {
+    (allow.is_empty() || allow.iter().any(|a| module_path.starts_with(a.as_str())))
+        && !deny.iter().any(|d| module_path.starts_with(d.as_str()))
+}
+
+fn main() {
+    let allow: Vec<String> = (0..40).map(|i| format!("app::service{i:02}")).collect();
+    let deny: Vec<String> = (0..10)
+        .map(|i| format!("app::service{i:02}::noisy"))
+        .collect();
+    let paths: Vec<String> = (0..64)
+        .map(|i| match i % 4 {
+            0 => format!("app::service{:02}::handler", i % 40),
+            1 => format!("app::service{:02}::noisy::tick", i % 10),
+            2 => format!("dep::crate{i}::inner"),
+            _ => format!("app::service{:02}", 39 - i % 40),
+        })
+        .collect();
+    let filter = ModuleFilter::new(allow.clone(), deny.clone());
+
+    for path in &paths {
+        assert_eq!(filter.matches(path), linear(path, &allow, &deny));
+    }
+
+    let mut hits = 0usize;
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        hits += usize::from(linear(black_box(&paths[i % paths.len()]), &allow, &deny));
+    }
+    let linear_time = start.elapsed();
+
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        hits -= usize::from(filter.matches(black_box(&paths[i % paths.len()])));
+    }
+    let compiled_time = start.elapsed();
+
+    let per_call = |d: std::time::Duration| d.as_nanos() as f64 / ITERATIONS as f64;
+    println!(
+        "{} entries, {ITERATIONS} checks ({hits} passed)",
+        allow.len() + deny.len()
+    );
+    println!("linear scan:      {:>7.1} ns/check", per_call(linear_time));
+    println!(
+        "compiled filter:  {:>7.1} ns/check",
+        per_call(compiled_time)
+    );
+    println!(
+        "speedup:          {:>7.1}x",
+        linear_time.as_secs_f64() / compiled_time.as_secs_f64()
+    );
+}
@@ -53,6 +53,7 @@
 //!
 //! - [`LogOutput::term`] — stderr with color support
 //! - [`LogOutput::writer`] — any `impl Write + Send` (files, buffers, etc.), plain text
+//! - [`LogOutput::file`] — appends plain text to a file path
 //! - [`LogOutput::test`] — via `print!()`, captured by Rust's test harness
 //!
 //! Multiple outputs can be added to a single logger, each with its own level
@@ -76,10 +77,12 @@
 //!
 //! - **Timestamps** — `.timestamps(true)` prepends `HH:MM:SS.mmm` via [nanotime](https://crates.io/crates/nanotime)
 //! - **Source location** — `.source_location(true)` appends `[file:line]` after the level tag
-//! - **Thread info** — `.thread_info(true)` shows `(thread-name)` or `(ThreadId(N))`
+//! - **Thread info** — `.thread_info(true)` shows `(thread-name)` or `(ThreadId(N))`; `.thread_id_style(ThreadIdStyle::Numeric)` shows `(N)` instead
 //! - **Module filtering** — `.module_allow()` / `.module_deny()` for prefix-based filtering
 //! - **Runtime level changes** — [`set_level`] adjusts the global level after init
+//! - **Level matching** — `.level_match()` switches between at-least, exact, and range level gates
 //! - **Env var** — `NANOLOGGER_LEVEL` sets the default level (case-insensitive)
+//! - **Output format** — `.output_format()` or `NANOLOG_FORMAT` switches between plain, JSON, and logfmt lines
 //!
 //! ## `log` facade integration
 //!
@@ -87,7 +90,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! nanologger = { version = "0.1.0", features = ["log"] }
+//! nanologger = { version = "0.2.0", features = ["log"] }
 //! ```
 //!
 //! When initialized, nanologger registers itself via `log::set_logger`, so
@@ -108,10 +111,11 @@
 //! info!("running nanologger {}", v);
 //! ```
 
-use std::fmt;
+use std::cell::Cell;
+use std::fmt::Write as _;
 use std::io::{IsTerminal, Write};
 use std::str::FromStr;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering};
 use std::sync::OnceLock;
 
 /// Log severity levels, ordered from highest to lowest severity.
@@ -124,29 +128,25 @@ pub enum LogLevel {
     Trace = 4,
 }
 
-impl fmt::Display for LogLevel {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let s = match self {
-            LogLevel::Error => "error",
-            LogLevel::Warn => "warn",
-            LogLevel::Info => "info",
-            LogLevel::Debug => "debug",
-            LogLevel::Trace => "trace",
-        };
-        f.write_str(s)
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str_lower())
     }
 }
 
+/// Parses a level name (`"warn"`), its first letter (`"w"`), or its numeric
+/// value (`"1"`, see [`LogLevel::as_u8`]). Names and letters are
+/// case-insensitive.
 impl FromStr for LogLevel {
     type Err = ParseLevelError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_ascii_lowercase().as_str() {
-            "error" => Ok(LogLevel::Error),
-            "warn" => Ok(LogLevel::Warn),
-            "info" => Ok(LogLevel::Info),
-            "debug" => Ok(LogLevel::Debug),
-            "trace" => Ok(LogLevel::Trace),
+            "error" | "e" | "0" => Ok(LogLevel::Error),
+            "warn" | "w" | "1" => Ok(LogLevel::Warn),
+            "info" | "i" | "2" => Ok(LogLevel::Info),
+            "debug" | "d" | "3" => Ok(LogLevel::Debug),
+            "trace" | "t" | "4" => Ok(LogLevel::Trace),
             _ => Err(ParseLevelError(s.to_string())),
         }
     }
@@ -156,8 +156,15 @@ impl FromStr for LogLevel {
 #[derive(Debug, Clone)]
 pub struct ParseLevelError(String);
 
-impl fmt::Display for ParseLevelError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl ParseLevelError {
+    /// Returns the string that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "invalid log level: '{}'", self.0)
     }
 }
@@ -165,7 +172,26 @@ impl fmt::Display for ParseLevelError {
 impl std::error::Error for ParseLevelError {}
 
 impl LogLevel {
-    /// Returns the bracketed, uppercase tag for log output, e.g. `[ERROR]`.
+    /// Every level, ordered from highest to lowest severity
+    /// (`Error`, `Warn`, `Info`, `Debug`, `Trace`).
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    /// Returns every level, ordered from highest to lowest severity.
+    pub fn all() -> [LogLevel; 5] {
+        Self::ALL
+    }
+
+    /// Returns an iterator over every level, from highest to lowest severity.
+    pub fn iter() -> std::array::IntoIter<LogLevel, 5> {
+        Self::ALL.into_iter()
+    }
+
     /// Converts a LogLevel to its u8 representation (matches the enum discriminant).
     pub fn as_u8(self) -> u8 {
         self as u8
@@ -183,15 +209,384 @@ impl LogLevel {
         }
     }
 
+    /// Shifts `base` by the net verbosity count from CLI flags such as `-v`
+    /// and `-q`. Each `verbose` step moves one level toward `Trace`, each
+    /// `quiet` step one level toward `Error`. The result is clamped to the
+    /// valid range.
+    ///
+    /// ```
+    /// use nanologger::LogLevel;
+    ///
+    /// assert_eq!(LogLevel::from_verbosity(LogLevel::Info, 2, 0), LogLevel::Trace);
+    /// assert_eq!(LogLevel::from_verbosity(LogLevel::Info, 0, 1), LogLevel::Warn);
+    /// assert_eq!(LogLevel::from_verbosity(LogLevel::Info, 0, 9), LogLevel::Error);
+    /// ```
+    pub fn from_verbosity(base: LogLevel, verbose: i32, quiet: i32) -> LogLevel {
+        let shifted = i64::from(base.as_u8()) + i64::from(verbose) - i64::from(quiet);
+        let clamped = shifted.clamp(0, i64::from(LogLevel::Trace.as_u8()));
+        LogLevel::from_u8(clamped as u8).unwrap_or(base)
+    }
+
+    /// Like [`from_verbosity`](Self::from_verbosity), counting the `v` and `q`
+    /// characters of a flag value such as `--verbose=vvv`. An empty string
+    /// returns `base`; any other character is an error.
+    ///
+    /// ```
+    /// use nanologger::LogLevel;
+    ///
+    /// assert_eq!(LogLevel::from_verbosity_str(LogLevel::Info, "vv").unwrap(), LogLevel::Trace);
+    /// assert_eq!(LogLevel::from_verbosity_str(LogLevel::Info, "q").unwrap(), LogLevel::Warn);
+    /// assert!(LogLevel::from_verbosity_str(LogLevel::Info, "v2").is_err());
+    /// ```
+    pub fn from_verbosity_str(base: LogLevel, s: &str) -> Result<LogLevel, ParseLevelError> {
+        let (mut verbose, mut quiet) = (0i32, 0i32);
+        for c in s.chars() {
+            match c {
+                'v' => verbose = verbose.saturating_add(1),
+                'q' => quiet = quiet.saturating_add(1),
+                _ => return Err(ParseLevelError(s.to_string())),
+            }
+        }
+        Ok(LogLevel::from_verbosity(base, verbose, quiet))
+    }
+
     /// Returns the bracketed, uppercase tag for log output, e.g. `[ERROR]`.
     /// Padded to 7 chars so all levels align.
     pub fn tag(&self) -> String {
+        self.tag_str().to_string()
+    }
+
+    /// The padded tag without allocating.
+    fn tag_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "[ERROR]",
+            LogLevel::Warn => "[WARN] ",
+            LogLevel::Info => "[INFO] ",
+            LogLevel::Debug => "[DEBUG]",
+            LogLevel::Trace => "[TRACE]",
+        }
+    }
+
+    /// Returns the syslog (RFC 5424) severity: Error `3`, Warn `4`, Info `6`,
+    /// and `7` for both Debug and Trace.
+    pub fn syslog_severity(self) -> u8 {
+        match self {
+            LogLevel::Error => 3,
+            LogLevel::Warn => 4,
+            LogLevel::Info => 6,
+            LogLevel::Debug | LogLevel::Trace => 7,
+        }
+    }
+
+    /// Returns the lowercase name, e.g. `"error"`. Same as `Display`.
+    pub fn as_str_lower(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+
+    /// Returns the uppercase name, e.g. `"ERROR"`.
+    pub fn as_str_upper(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+
+    /// Returns a three-letter uppercase abbreviation, e.g. `"ERR"`.
+    pub fn as_str_short(self) -> &'static str {
         match self {
-            LogLevel::Error => "[ERROR]".to_string(),
-            LogLevel::Warn => "[WARN] ".to_string(),
-            LogLevel::Info => "[INFO] ".to_string(),
-            LogLevel::Debug => "[DEBUG]".to_string(),
-            LogLevel::Trace => "[TRACE]".to_string(),
+            LogLevel::Error => "ERR",
+            LogLevel::Warn => "WRN",
+            LogLevel::Info => "INF",
+            LogLevel::Debug => "DBG",
+            LogLevel::Trace => "TRC",
+        }
+    }
+
+    /// The default color of the level tag.
+    fn default_color(self) -> Color {
+        match self {
+            LogLevel::Error => Color::Red,
+            LogLevel::Warn => Color::Yellow,
+            LogLevel::Info => Color::Green,
+            LogLevel::Debug => Color::Blue,
+            LogLevel::Trace => Color::Magenta,
+        }
+    }
+}
+
+/// Controls how a message's level is compared against a level threshold.
+///
+/// Consulted by both the global level gate and each output's own filter.
+/// The default, [`LevelMatch::AtLeast`], emits everything at or above the
+/// threshold's severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelMatch {
+    /// Emit messages at the threshold or more severe (the default).
+    #[default]
+    AtLeast,
+    /// Emit only messages exactly at the threshold.
+    Exact,
+    /// Emit only messages between the two levels, inclusive. The threshold is
+    /// ignored and the bounds may be given in either order.
+    Range(LogLevel, LogLevel),
+}
+
+impl LevelMatch {
+    /// Returns `true` if a message at `level` passes a filter set to `threshold`.
+    pub fn allows(self, level: LogLevel, threshold: LogLevel) -> bool {
+        match self {
+            LevelMatch::AtLeast => level <= threshold,
+            LevelMatch::Exact => level == threshold,
+            LevelMatch::Range(a, b) => level >= a.min(b) && level <= a.max(b),
+        }
+    }
+}
+
+/// Human-readable wrappers for common log message values.
+///
+/// Each helper returns a small `Display` type meant to be dropped straight
+/// into format arguments:
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// nanologger::info!("took {}", nanologger::fmt::duration(Duration::from_millis(1250)));
+/// nanologger::info!("wrote {}", nanologger::fmt::bytes(1_468_006));
+/// // [INFO]  took 1.25s
+/// // [INFO]  wrote 1.4 MiB
+/// ```
+pub mod fmt {
+    use std::time::Duration;
+
+    /// Formats a [`Duration`] with the largest fitting unit, e.g. `1.25s`,
+    /// `350ms`, `12.5µs`, `800ns`. At most two decimals are shown.
+    pub fn duration(d: Duration) -> HumanDuration {
+        HumanDuration(d)
+    }
+
+    /// Formats a byte count with binary units, e.g. `512 B`, `1.4 MiB`.
+    pub fn bytes(n: u64) -> HumanBytes {
+        HumanBytes(n)
+    }
+
+    /// `Display` wrapper returned by [`duration`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct HumanDuration(pub Duration);
+
+    impl std::fmt::Display for HumanDuration {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let nanos = self.0.as_nanos();
+            let (mut value, mut unit) = if nanos >= 1_000_000_000 {
+                (self.0.as_secs_f64(), "s")
+            } else if nanos >= 1_000_000 {
+                (nanos as f64 / 1e6, "ms")
+            } else if nanos >= 1_000 {
+                (nanos as f64 / 1e3, "µs")
+            } else {
+                return write!(f, "{nanos}ns");
+            };
+            // Rounding can carry into the next unit: 999.999ms is 1s.
+            if (value * 100.0).round() >= 100_000.0 && unit != "s" {
+                value = 1.0;
+                unit = if unit == "µs" { "ms" } else { "s" };
+            }
+            let fixed = format!("{value:.2}");
+            let trimmed = fixed.trim_end_matches('0').trim_end_matches('.');
+            write!(f, "{trimmed}{unit}")
+        }
+    }
+
+    /// `Display` wrapper returned by [`bytes`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct HumanBytes(pub u64);
+
+    impl std::fmt::Display for HumanBytes {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+            if self.0 < 1024 {
+                return write!(f, "{} B", self.0);
+            }
+            let mut value = self.0 as f64 / 1024.0;
+            let mut unit = 0;
+            // Compare the rounded value, so 1023.99 KiB shows as 1.0 MiB.
+            while (value * 10.0).round() >= 10240.0 && unit < UNITS.len() - 1 {
+                value /= 1024.0;
+                unit += 1;
+            }
+            write!(f, "{value:.1} {}", UNITS[unit])
+        }
+    }
+}
+
+/// MessagePack encoding for [`LogOutput::msgpack`] (`msgpack` feature).
+///
+/// Each record is written as a 4-byte big-endian length followed by that many
+/// bytes holding one MessagePack map with these keys:
+///
+/// | Key | Type | Present |
+/// |-----|------|---------|
+/// | `seq` | uint | with [`LoggerBuilder::sequence_numbers`] |
+/// | `ts` | str | when timestamps are enabled for the output |
+/// | `thread` | str | when thread info is enabled for the output |
+/// | `level` | uint | always; `0` = Error … `4` = Trace, see [`LogLevel::as_u8`] |
+/// | `app` | str | with [`LoggerBuilder::app_name`] |
+/// | `host` | str | with [`LoggerBuilder::hostname`] or the `hostname` feature |
+/// | `build` | str | with [`LoggerBuilder::build_id`] |
+/// | `module` | str | always; may be empty |
+/// | `file` | str | always |
+/// | `line` | uint | always |
+/// | `msg` | str | always |
+/// | `fields` | map of str to str, int, uint, float64, or bool | when the record has [`log_fields`] / [`log_kv!`] fields |
+#[cfg(feature = "msgpack")]
+pub mod msgpack {
+    use super::{FieldValue, FormatOptions, Record, Segments};
+
+    /// Appends one length-prefixed record to `out`.
+    pub(crate) fn encode_record(
+        out: &mut Vec<u8>,
+        opts: &FormatOptions,
+        record: &Record<'_>,
+        segments: &Segments<'_>,
+    ) {
+        let start = out.len();
+        out.extend_from_slice(&[0; 4]);
+        let fields = 5
+            + usize::from(segments.sequence.is_some())
+            + usize::from(segments.timestamp.is_some())
+            + usize::from(segments.thread_info.is_some())
+            + usize::from(opts.app.is_some())
+            + usize::from(opts.host.is_some())
+            + usize::from(opts.build.is_some())
+            + usize::from(!segments.fields.is_empty());
+        write_map_len(out, fields);
+        if let Some(seq) = segments.sequence {
+            write_str(out, "seq");
+            write_uint(out, seq);
+        }
+        if let Some(ts) = segments.timestamp {
+            write_str(out, "ts");
+            write_str(out, ts);
+        }
+        if let Some(thread) = segments.thread_info {
+            write_str(out, "thread");
+            write_str(out, thread);
+        }
+        write_str(out, "level");
+        write_uint(out, u64::from(record.level.as_u8()));
+        if let Some(app) = &opts.app {
+            write_str(out, "app");
+            write_str(out, app);
+        }
+        if let Some(host) = &opts.host {
+            write_str(out, "host");
+            write_str(out, host);
+        }
+        if let Some(build) = &opts.build {
+            write_str(out, "build");
+            write_str(out, build);
+        }
+        write_str(out, "module");
+        write_str(out, record.module_path);
+        write_str(out, "file");
+        write_str(out, record.file);
+        write_str(out, "line");
+        write_uint(out, u64::from(record.line));
+        write_str(out, "msg");
+        write_str(out, record.message);
+        if !segments.fields.is_empty() {
+            write_str(out, "fields");
+            write_map_len(out, segments.fields.len());
+            for (key, value) in segments.fields {
+                write_str(out, key);
+                match *value {
+                    FieldValue::Str(s) => write_str(out, s),
+                    FieldValue::I64(n) => write_int(out, n),
+                    FieldValue::U64(n) => write_uint(out, n),
+                    FieldValue::F64(n) => {
+                        out.push(0xcb);
+                        out.extend_from_slice(&n.to_be_bytes());
+                    }
+                    FieldValue::Bool(b) => out.push(if b { 0xc3 } else { 0xc2 }),
+                }
+            }
+        }
+        let len = (out.len() - start - 4) as u32;
+        out[start..start + 4].copy_from_slice(&len.to_be_bytes());
+    }
+
+    fn write_map_len(out: &mut Vec<u8>, len: usize) {
+        if len < 16 {
+            out.push(0x80 | len as u8);
+        } else if len <= u16::MAX as usize {
+            out.push(0xde);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            out.push(0xdf);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+
+    fn write_str(out: &mut Vec<u8>, s: &str) {
+        let len = s.len();
+        if len < 32 {
+            out.push(0xa0 | len as u8);
+        } else if len <= u8::MAX as usize {
+            out.push(0xd9);
+            out.push(len as u8);
+        } else if len <= u16::MAX as usize {
+            out.push(0xda);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            out.push(0xdb);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    fn write_uint(out: &mut Vec<u8>, n: u64) {
+        if n < 128 {
+            out.push(n as u8);
+        } else if n <= u64::from(u8::MAX) {
+            out.push(0xcc);
+            out.push(n as u8);
+        } else if n <= u64::from(u16::MAX) {
+            out.push(0xcd);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        } else if n <= u64::from(u32::MAX) {
+            out.push(0xce);
+            out.extend_from_slice(&(n as u32).to_be_bytes());
+        } else {
+            out.push(0xcf);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+
+    fn write_int(out: &mut Vec<u8>, n: i64) {
+        if n >= 0 {
+            write_uint(out, n as u64);
+        } else if n >= -32 {
+            out.push(n as u8);
+        } else if n >= i64::from(i8::MIN) {
+            out.push(0xd0);
+            out.push(n as u8);
+        } else if n >= i64::from(i16::MIN) {
+            out.push(0xd1);
+            out.extend_from_slice(&(n as i16).to_be_bytes());
+        } else if n >= i64::from(i32::MIN) {
+            out.push(0xd2);
+            out.extend_from_slice(&(n as i32).to_be_bytes());
+        } else {
+            out.push(0xd3);
+            out.extend_from_slice(&n.to_be_bytes());
         }
     }
 }
@@ -199,7 +594,265 @@ impl LogLevel {
 // Re-export nanocolor's Colorize trait so users can style log message content
 // without adding nanocolor as a separate dependency.
 pub use nanocolor::Colorize;
-pub use nanocolor::{style, StyledString};
+pub use nanocolor::{style, Color, StyledString};
+
+/// A color for a [`ColorTheme`]: one of the 16 basic ANSI colors, an index
+/// into the 256-color palette, or a 24-bit RGB value.
+///
+/// Extended colors fall back to the nearest color the terminal advertises:
+/// truecolor needs `COLORTERM=truecolor` (or `24bit`), and 256 colors need
+/// truecolor or a `TERM` containing `256color`. Otherwise the nearest basic
+/// color is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeColor {
+    /// One of the 16 basic ANSI colors.
+    Basic(Color),
+    /// An index into the 256-color palette.
+    Ansi256(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+impl From<Color> for ThemeColor {
+    fn from(color: Color) -> Self {
+        ThemeColor::Basic(color)
+    }
+}
+
+/// The 16 basic colors with their usual xterm RGB values, for nearest-color fallback.
+const BASIC_PALETTE: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::BrightBlack, (127, 127, 127)),
+    (Color::BrightRed, (255, 0, 0)),
+    (Color::BrightGreen, (0, 255, 0)),
+    (Color::BrightYellow, (255, 255, 0)),
+    (Color::BrightBlue, (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan, (0, 255, 255)),
+    (Color::BrightWhite, (255, 255, 255)),
+];
+
+impl ThemeColor {
+    /// Returns the SGR foreground parameter for this color at the given depth.
+    fn fg_sgr(self, depth: ColorDepth) -> String {
+        let mut out = String::new();
+        self.write_fg_sgr(&mut out, depth);
+        out
+    }
+
+    /// Appends the SGR foreground parameter for this color at the given depth.
+    fn write_fg_sgr(self, out: &mut String, depth: ColorDepth) {
+        // Writing to a String cannot fail.
+        let _ = match (self, depth) {
+            (ThemeColor::Basic(color), _) => write!(out, "{}", color.fg_code()),
+            (ThemeColor::Rgb(r, g, b), ColorDepth::TrueColor) => write!(out, "38;2;{r};{g};{b}"),
+            (ThemeColor::Rgb(r, g, b), ColorDepth::Ansi256) => {
+                let level = |c: u8| (u16::from(c) * 5 + 127) / 255;
+                write!(out, "38;5;{}", 16 + 36 * level(r) + 6 * level(g) + level(b))
+            }
+            (ThemeColor::Ansi256(n), ColorDepth::Ansi256 | ColorDepth::TrueColor) => {
+                write!(out, "38;5;{n}")
+            }
+            (color, ColorDepth::Basic) => write!(out, "{}", color.nearest_basic().fg_code()),
+        };
+    }
+
+    /// Returns the basic color closest to this one.
+    fn nearest_basic(self) -> Color {
+        let (r, g, b) = match self {
+            ThemeColor::Basic(color) => return color,
+            ThemeColor::Ansi256(n) if n < 16 => return BASIC_PALETTE[n as usize].0,
+            ThemeColor::Ansi256(n) if n >= 232 => {
+                let v = 8 + (n - 232) * 10;
+                (v, v, v)
+            }
+            ThemeColor::Ansi256(n) => {
+                let step = |i: u8| if i == 0 { 0 } else { 55 + i * 40 };
+                let i = n - 16;
+                (step(i / 36), step(i / 6 % 6), step(i % 6))
+            }
+            ThemeColor::Rgb(r, g, b) => (r, g, b),
+        };
+        let distance = |(pr, pg, pb): (u8, u8, u8)| {
+            let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+            d(r, pr) + d(g, pg) + d(b, pb)
+        };
+        BASIC_PALETTE
+            .iter()
+            .min_by_key(|(_, rgb)| distance(*rgb))
+            .map(|(color, _)| *color)
+            .unwrap_or(Color::White)
+    }
+}
+
+/// How many colors the terminal advertises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub(crate) enum ColorDepth {
+    #[default]
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Reads the depth from the `COLORTERM` and `TERM` environment variables.
+    fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        if std::env::var("TERM").is_ok_and(|term| term.contains("256color")) {
+            return ColorDepth::Ansi256;
+        }
+        ColorDepth::Basic
+    }
+}
+
+/// A color per log level. Levels without a color are left unstyled.
+///
+/// Accepts basic [`Color`]s as well as 256-color and RGB [`ThemeColor`]s:
+///
+/// ```rust
+/// use nanologger::{Color, ColorTheme, LogLevel, ThemeColor};
+///
+/// let theme = ColorTheme::new()
+///     .with(LogLevel::Error, Color::BrightRed)
+///     .with(LogLevel::Trace, ThemeColor::Rgb(120, 120, 120));
+/// assert_eq!(theme.get(LogLevel::Error), Some(ThemeColor::Basic(Color::BrightRed)));
+/// assert_eq!(theme.get(LogLevel::Info), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColorTheme {
+    colors: [Option<ThemeColor>; 5],
+}
+
+impl ColorTheme {
+    /// Creates a theme with no colors set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the color for `level`.
+    pub fn set(&mut self, level: LogLevel, color: impl Into<ThemeColor>) {
+        self.colors[level.as_u8() as usize] = Some(color.into());
+    }
+
+    /// Returns the theme with the color for `level` set, for chaining.
+    pub fn with(mut self, level: LogLevel, color: impl Into<ThemeColor>) -> Self {
+        self.set(level, color);
+        self
+    }
+
+    /// Returns the color for `level`, if one is set.
+    pub fn get(&self, level: LogLevel) -> Option<ThemeColor> {
+        self.colors[level.as_u8() as usize]
+    }
+}
+
+/// How the message text (as opposed to the level tag) is colored when color
+/// output is active.
+///
+/// ANSI styling already present in the message, e.g. from [`Colorize`],
+/// takes precedence over the policy for the styled span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageColorPolicy {
+    /// Leave message text unstyled (the default).
+    #[default]
+    None,
+    /// Dim messages less severe than the given level, e.g. `DimBelow(Info)`
+    /// dims Debug and Trace messages.
+    DimBelow(LogLevel),
+    /// Color message text per level.
+    PerLevel(ColorTheme),
+}
+
+impl MessageColorPolicy {
+    /// Returns the SGR parameter to apply to a message at `level`, if any.
+    fn sgr(&self, level: LogLevel, depth: ColorDepth) -> Option<String> {
+        match self {
+            MessageColorPolicy::None => None,
+            MessageColorPolicy::DimBelow(threshold) if level > *threshold => {
+                Some(nanocolor::Style::Dim.code().to_string())
+            }
+            MessageColorPolicy::DimBelow(_) => None,
+            MessageColorPolicy::PerLevel(theme) => theme.get(level).map(|c| c.fg_sgr(depth)),
+        }
+    }
+}
+
+/// A style for a whole colored line, returned by a
+/// [`color_rule`](LoggerBuilder::color_rule).
+///
+/// ```rust
+/// use nanologger::{Color, LineStyle};
+///
+/// let alert = LineStyle::new(Color::BrightRed).bold();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineStyle {
+    color: ThemeColor,
+    bold: bool,
+}
+
+impl LineStyle {
+    /// A style with the given foreground color.
+    pub fn new(color: impl Into<ThemeColor>) -> Self {
+        LineStyle {
+            color: color.into(),
+            bold: false,
+        }
+    }
+
+    /// Makes the line bold as well.
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Returns the SGR parameters for this style at the given depth.
+    fn sgr(self, depth: ColorDepth) -> String {
+        let mut out = String::from(if self.bold { "1;" } else { "" });
+        self.color.write_fg_sgr(&mut out, depth);
+        out
+    }
+}
+
+/// Picks a [`LineStyle`] per record; see [`LoggerBuilder::color_rule`].
+type ColorRule = Box<dyn Fn(&LogRecord) -> Option<LineStyle> + Send + Sync>;
+
+/// Wraps `message` in the SGR style `sgr`. Escape sequences already in the
+/// message win: the outer style is cleared before each inner sequence and
+/// restored after each inner reset.
+fn paint_message(message: &str, sgr: &str) -> String {
+    let open = format!("\x1b[{sgr}m");
+    let mut out = open.clone();
+    let mut rest = message;
+    while let Some(start) = rest.find("\x1b[") {
+        let Some(len) = rest[start..].find('m').map(|i| i + 1) else {
+            break;
+        };
+        let seq = &rest[start..start + len];
+        out.push_str(&rest[..start]);
+        if seq == "\x1b[0m" {
+            out.push_str(seq);
+            out.push_str(&open);
+        } else {
+            out.push_str("\x1b[0m");
+            out.push_str(seq);
+        }
+        rest = &rest[start + len..];
+    }
+    out.push_str(rest);
+    out.push_str("\x1b[0m");
+    out
+}
 
 /// Formats a log message with an optional colored, bold level prefix.
 ///
@@ -221,11 +874,8 @@ pub(crate) fn format_message_with_timestamp(
     format_message_full(level, message, use_color, timestamp, None, None)
 }
 
-/// Core formatting function. Produces the full log line with optional timestamp
-/// and optional source location.
-///
-/// Format: `{timestamp} {bold_colored_prefix} [{file}:{line}] {message_text}\n`
-/// Segments are omitted when `None`.
+/// Formats a log line with the default [`FormatOptions`].
+#[cfg(test)]
 pub(crate) fn format_message_full(
     level: LogLevel,
     message: &str,
@@ -234,417 +884,4906 @@ pub(crate) fn format_message_full(
     source_loc: Option<(&str, u32)>,
     thread_info: Option<&str>,
 ) -> String {
-    let tag = level.tag();
-    let ts_part = match timestamp {
-        Some(ts) => format!("{ts} "),
-        None => String::new(),
-    };
-    let thread_part = match thread_info {
-        Some(info) => format!("({info}) "),
-        None => String::new(),
+    let segments = Segments {
+        timestamp,
+        source_loc,
+        thread_info,
+        ..Segments::default()
     };
-    let loc_part = match source_loc {
-        Some((file, line)) => format!("[{file}:{line}] "),
-        None => String::new(),
-    };
-    if use_color {
-        let styled = match level {
-            LogLevel::Error => tag.red().bold().to_string(),
-            LogLevel::Warn => tag.yellow().bold().to_string(),
-            LogLevel::Info => tag.green().bold().to_string(),
-            LogLevel::Debug => tag.blue().bold().to_string(),
-            LogLevel::Trace => tag.magenta().bold().to_string(),
-        };
-        format!("{ts_part}{thread_part}{styled} {loc_part}{message}\n")
-    } else {
-        format!("{ts_part}{thread_part}{tag} {loc_part}{message}\n")
-    }
+    format_line(
+        &FormatOptions::default(),
+        level,
+        message,
+        use_color,
+        &segments,
+    )
 }
 
-/// Returns `true` if a message from `module_path` should be emitted given the
-/// allow and deny lists.
-///
-/// - If `allow` is empty, all modules pass the allow check.
-/// - Otherwise, `module_path` must start with at least one entry in `allow`.
-/// - After the allow check, the module is rejected if `module_path` starts with
-///   any entry in `deny`.
-pub fn matches_module_filter(module_path: &str, allow: &[String], deny: &[String]) -> bool {
-    let allowed = allow.is_empty() || allow.iter().any(|a| module_path.starts_with(a.as_str()));
-    if !allowed {
-        return false;
+/// Line terminator appended to every log record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    /// `\n` (the default).
+    #[default]
+    Lf,
+    /// `\r\n`.
+    CrLf,
+    /// `\r\n` on Windows, `\n` everywhere else.
+    Platform,
+}
+
+impl LineEnding {
+    /// Returns the terminator as a string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Platform if cfg!(windows) => "\r\n",
+            LineEnding::Platform => "\n",
+        }
     }
-    !deny.iter().any(|d| module_path.starts_with(d.as_str()))
 }
 
-/// Represents a log output destination.
-///
-/// Each variant carries its own level filter. `Term` writes colored output to
-/// stderr while `Writer` writes plain text to an arbitrary `Write` destination.
-pub enum LogOutput {
-    /// Logs to stderr with optional color support.
-    Term { level: LogLevel },
-    /// Logs to an arbitrary `Write` destination in plain text.
-    Writer {
-        level: LogLevel,
-        writer: std::sync::Mutex<Box<dyn Write + Send>>,
-    },
-    /// Logs plain text via `print!()`, captured by Rust's test harness.
-    Test { level: LogLevel },
+/// How [`LogOutput::writer`], `file`, and `tee` outputs encode text to
+/// bytes, set with [`LogOutput::encoding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// UTF-8 without a byte order mark (the default).
+    #[default]
+    Utf8,
+    /// UTF-8, preceded by the `EF BB BF` byte order mark at the start of the
+    /// destination.
+    Utf8Bom,
+    /// UTF-16 little-endian, preceded by the `FF FE` byte order mark at the
+    /// start of the destination, as Windows tools such as Notepad expect.
+    Utf16Le,
 }
 
-impl LogOutput {
-    /// Creates a `Term` output that writes to stderr at the given level.
-    pub fn term(level: LogLevel) -> Self {
-        LogOutput::Term { level }
+impl Encoding {
+    /// The byte order mark written before the first line.
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => b"",
+            Encoding::Utf8Bom => b"\xEF\xBB\xBF",
+            Encoding::Utf16Le => b"\xFF\xFE",
+        }
     }
 
-    /// Creates a `Writer` output that writes plain text to the given destination.
-    pub fn writer(level: LogLevel, w: impl Write + Send + 'static) -> Self {
-        LogOutput::Writer {
-            level,
-            writer: std::sync::Mutex::new(Box::new(w)),
+    /// Encodes `line`, prefixed with the byte order mark when `bom` is set.
+    fn encode(self, line: &str, bom: bool) -> std::borrow::Cow<'_, [u8]> {
+        let bom = if bom { self.bom() } else { b"" };
+        match self {
+            Encoding::Utf8 | Encoding::Utf8Bom if bom.is_empty() => line.as_bytes().into(),
+            Encoding::Utf8 | Encoding::Utf8Bom => [bom, line.as_bytes()].concat().into(),
+            Encoding::Utf16Le => {
+                let mut bytes = bom.to_vec();
+                bytes.extend(line.encode_utf16().flat_map(u16::to_le_bytes));
+                bytes.into()
+            }
         }
     }
+}
 
-    /// Creates a `Test` output that writes plain text via `print!()`.
-    /// Output is captured by Rust's test harness.
-    pub fn test(level: LogLevel) -> Self {
-        LogOutput::Test { level }
+/// How each record is rendered.
+///
+/// `Json` and `Logfmt` emit one machine-readable object per line with the
+/// keys `seq`, `ts`, `thread`, `level`, `app`, `host`, `build`, `crate`,
+/// `module`, `file`, `line`, and `msg`, followed by any [`log_fields`]
+/// fields. Keys for disabled segments are omitted. Structured formats are
+/// never colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// The human-readable `[LEVEL] message` layout (the default).
+    #[default]
+    Plain,
+    /// One JSON object per line.
+    Json,
+    /// One line of `key=value` pairs.
+    Logfmt,
+}
+
+impl OutputFormat {
+    /// Returns the lowercase name, as accepted by `NANOLOG_FORMAT`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputFormat::Plain => "plain",
+            OutputFormat::Json => "json",
+            OutputFormat::Logfmt => "logfmt",
+        }
     }
 }
 
-// ---------------------------------------------------------------------------
-// Global Logger
-// ---------------------------------------------------------------------------
+impl FromStr for OutputFormat {
+    type Err = ParseFormatError;
 
-/// The global logger. Immutable after initialization.
-/// The global logger. Immutable after initialization.
-pub struct Logger {
-    level: AtomicU8,
-    timestamps: bool,
-    source_location: bool,
-    thread_info: bool,
-    module_allow: Vec<String>,
-    module_deny: Vec<String>,
-    outputs: Vec<LogOutput>,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" => Ok(OutputFormat::Plain),
+            "json" => Ok(OutputFormat::Json),
+            "logfmt" => Ok(OutputFormat::Logfmt),
+            _ => Err(ParseFormatError(s.to_string())),
+        }
+    }
 }
 
-static LOGGER: OnceLock<Logger> = OnceLock::new();
+/// Error returned when parsing an invalid output format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFormatError(String);
 
-/// Formats the current local time as `HH:MM:SS.mmm` using nanotime.
-fn format_current_timestamp() -> String {
-    nanotime::NanoTime::now().to_string()
+impl ParseFormatError {
+    /// Returns the string that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.0
+    }
 }
 
-impl Logger {
-    /// Returns the configured log level.
-    pub fn level(&self) -> LogLevel {
-        LogLevel::from_u8(self.level.load(Ordering::Relaxed)).unwrap_or(LogLevel::Info)
+impl std::fmt::Display for ParseFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid output format: {:?}", self.0)
     }
 }
 
-/// Builder for configuring and initializing the global Logger.
-/// Builder for configuring and initializing the global Logger.
-pub struct LoggerBuilder {
-    level: LogLevel,
-    timestamps: bool,
-    source_location: bool,
-    thread_info: bool,
-    module_allow: Vec<String>,
-    module_deny: Vec<String>,
-    outputs: Vec<LogOutput>,
+impl std::error::Error for ParseFormatError {}
+
+/// How plain-text output handles messages that contain line breaks.
+///
+/// JSON and logfmt always escape line breaks, whatever the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MultilinePolicy {
+    /// Write the message as-is, line breaks included (the default).
+    #[default]
+    Raw,
+    /// Replace `\n` and `\r` with the two-character escapes `\\n` and `\\r`.
+    Escape,
+    /// Write each line of the message as its own line with the full prefix.
+    Split,
 }
 
-impl LoggerBuilder {
-    /// Creates a new builder with the default level (`Info`) and timestamps disabled.
-    pub fn new() -> Self {
-        let default_level = std::env::var("NANOLOGGER_LEVEL")
-            .ok()
-            .and_then(|s| LogLevel::from_str(&s).ok())
-            .unwrap_or(LogLevel::Info);
+/// How plain-text output shows the record's level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelStyle {
+    /// A padded, colored tag such as `[ERROR]` after the timestamp and thread
+    /// info (the default).
+    #[default]
+    Bracketed,
+    /// The [syslog severity](LogLevel::syslog_severity) as an uncolored `<N>`
+    /// prefix at the very start of the line, e.g. `<3>disk full`. This is the
+    /// sd-daemon convention that journald and other collectors parse.
+    SyslogNumeric,
+}
 
-        Self {
-            level: default_level,
-            timestamps: false,
-            source_location: false,
-            thread_info: false,
-            module_allow: Vec::new(),
-            module_deny: Vec::new(),
-            outputs: Vec::new(),
-        }
-    }
+/// The punctuation around the plain-text source location, set with
+/// [`LoggerBuilder::source_location_format`]. Defaults to `[file:line]`.
+///
+/// ```
+/// use nanologger::SourceLocationFormat;
+///
+/// // (main.rs:42)
+/// let parens = SourceLocationFormat::new().delimiters("(", ")").basename(true);
+/// // @src/main.rs#42
+/// let at = SourceLocationFormat::new().delimiters("@", "").separator("#");
+/// # let _ = (parens, at);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocationFormat {
+    open: &'static str,
+    close: &'static str,
+    separator: &'static str,
+    basename: bool,
+}
 
-    /// Sets the minimum log level.
-    pub fn level(mut self, level: LogLevel) -> Self {
-        self.level = level;
-        self
+impl Default for SourceLocationFormat {
+    fn default() -> Self {
+        SourceLocationFormat {
+            open: "[",
+            close: "]",
+            separator: ":",
+            basename: false,
+        }
     }
+}
 
-    /// Returns the currently configured log level.
-    pub fn get_level(&self) -> LogLevel {
-        self.level
+impl SourceLocationFormat {
+    /// Creates the default `[file:line]` format.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Enables or disables timestamp prefixes (`HH:MM:SS`) on log messages.
-    pub fn timestamps(mut self, enabled: bool) -> Self {
-        self.timestamps = enabled;
+    /// Sets the text before and after the location; `("", "")` leaves it bare.
+    pub fn delimiters(mut self, open: &'static str, close: &'static str) -> Self {
+        self.open = open;
+        self.close = close;
         self
     }
 
-    /// Enables or disables source location (`[file:line]`) in log output.
-    pub fn source_location(mut self, enabled: bool) -> Self {
-        self.source_location = enabled;
+    /// Sets the text between the file and the line number. Defaults to `:`.
+    pub fn separator(mut self, separator: &'static str) -> Self {
+        self.separator = separator;
         self
     }
 
-    /// Enables or disables thread info (thread name or ID) in log output.
-    pub fn thread_info(mut self, enabled: bool) -> Self {
-        self.thread_info = enabled;
+    /// Shows only the file name instead of the path `file!()` reports.
+    pub fn basename(mut self, enabled: bool) -> Self {
+        self.basename = enabled;
         self
     }
 
-    /// Sets the module allow list. Only messages from modules whose paths start
-    /// with an entry in this list will be emitted.
-    pub fn module_allow(mut self, modules: Vec<String>) -> Self {
-        self.module_allow = modules;
-        self
+    /// Appends the location and a trailing space to `out`.
+    fn write_into(&self, out: &mut String, file: &str, line: u32) {
+        let file = if self.basename {
+            file.rsplit(['/', '\\']).next().unwrap_or(file)
+        } else {
+            file
+        };
+        let _ = write!(
+            out,
+            "{}{file}{}{line}{} ",
+            self.open, self.separator, self.close
+        );
     }
+}
 
-    /// Sets the module deny list. Messages from modules whose paths start with
-    /// an entry in this list will be discarded.
-    pub fn module_deny(mut self, modules: Vec<String>) -> Self {
-        self.module_deny = modules;
-        self
+/// Whether [`LogOutput::term`] outputs use ANSI colors, set with
+/// [`LoggerBuilder::color`]. Other outputs are always plain text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color when stderr is a TTY (or a [`term_writer`](LogOutput::term_writer)
+    /// is set), unless `NO_COLOR` or nanocolor's overrides say otherwise (the
+    /// default).
+    #[default]
+    Auto,
+    /// Always color, even when stderr is piped and regardless of `NO_COLOR`.
+    Always,
+    /// Never color.
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether ANSI codes may be written to an output that wants color.
+    fn allows_ansi(self) -> bool {
+        match self {
+            ColorChoice::Auto => nanocolor::colors_enabled(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
     }
+}
 
-    /// Adds a log output destination. Multiple outputs can be added; each
-    /// applies its own level filter independently.
-    pub fn add_output(mut self, output: LogOutput) -> Self {
-        self.outputs.push(output);
-        self
+/// Logger-wide formatting settings applied to every output.
+#[derive(Debug, Clone)]
+pub(crate) struct FormatOptions {
+    pub(crate) output_format: OutputFormat,
+    pub(crate) level_style: LevelStyle,
+    /// When `false`, plain text leaves out the `[LEVEL]` tag.
+    pub(crate) show_level_tag: bool,
+    pub(crate) source_format: SourceLocationFormat,
+    /// Show the crate name from the module path after the level tag.
+    pub(crate) crate_tag: bool,
+    /// Emit `crate` and `module` keys in structured output.
+    pub(crate) split_target: bool,
+    pub(crate) multiline: MultilinePolicy,
+    pub(crate) line_ending: LineEnding,
+    /// When `false`, no line ending is appended at all.
+    pub(crate) trailing_newline: bool,
+    /// Pre-rendered `app` or `app@version` segment.
+    pub(crate) app: Option<String>,
+    /// Source hostname, shown after the app segment.
+    pub(crate) host: Option<String>,
+    /// `host` came from the `hostname` feature rather than
+    /// [`LoggerBuilder::hostname`], so it is only emitted in structured output.
+    pub(crate) host_detected: bool,
+    /// Build identifier, shown after the hostname.
+    pub(crate) build: Option<String>,
+    pub(crate) color: ColorChoice,
+    pub(crate) message_color: MessageColorPolicy,
+    /// Overrides for the level tag colors.
+    pub(crate) level_colors: ColorTheme,
+    /// Colors the terminal supports, detected at init.
+    pub(crate) color_depth: ColorDepth,
+    pub(crate) align: MessageAlign,
+    /// With [`LoggerBuilder::fixed_ts_level_field`]: the combined timestamp
+    /// and tag field's width.
+    pub(crate) ts_level_field: Option<TsLevelField>,
+}
+
+/// The width of the combined timestamp and level tag field, set by
+/// [`LoggerBuilder::fixed_ts_level_field`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TsLevelField {
+    /// Width of the configured clock's timestamps, measured at init.
+    timestamp: usize,
+    /// Width of the widest level tag and its space; 0 when tags are hidden.
+    tag: usize,
+    /// How far timestamps wider than `timestamp`, e.g. from [`log_at!`], have
+    /// pushed the field out so far. Shared by every output.
+    overflow: std::sync::Arc<AtomicUsize>,
+}
+
+/// Where plain-text messages start, set by [`LoggerBuilder::align_messages`]
+/// and [`LoggerBuilder::message_column`].
+#[derive(Debug, Clone, Default)]
+pub(crate) enum MessageAlign {
+    #[default]
+    Off,
+    /// The widest metadata seen so far, shared by every output.
+    Auto(std::sync::Arc<AtomicUsize>),
+    /// A fixed column.
+    Column(usize),
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            output_format: OutputFormat::Plain,
+            level_style: LevelStyle::Bracketed,
+            show_level_tag: true,
+            source_format: SourceLocationFormat::default(),
+            crate_tag: false,
+            split_target: false,
+            multiline: MultilinePolicy::Raw,
+            line_ending: LineEnding::Lf,
+            trailing_newline: true,
+            app: None,
+            host: None,
+            host_detected: false,
+            build: None,
+            color: ColorChoice::Auto,
+            message_color: MessageColorPolicy::None,
+            level_colors: ColorTheme::new(),
+            color_depth: ColorDepth::Basic,
+            align: MessageAlign::Off,
+            ts_level_field: None,
+        }
     }
+}
 
-    /// Initializes the global logger. Returns `Err(InitError)` if already initialized.
-    ///
-    /// When the `log` feature is enabled, this also registers the logger with the
-    /// `log` facade via `log::set_logger` and `log::set_max_level`.
-    pub fn init(self) -> Result<(), InitError> {
-        let outputs = if self.outputs.is_empty() {
-            vec![LogOutput::Term { level: self.level }]
-        } else {
-            self.outputs
-        };
-        let logger = Logger {
-            level: AtomicU8::new(self.level.as_u8()),
-            timestamps: self.timestamps,
-            source_location: self.source_location,
-            thread_info: self.thread_info,
-            module_allow: self.module_allow,
-            module_deny: self.module_deny,
-            outputs,
-        };
-        LOGGER.set(logger).map_err(|_| InitError)?;
+/// A typed structured field value, as attached by [`log_kv!`].
+///
+/// JSON and MessagePack emit numbers and booleans as native values instead of
+/// strings; text formats render every variant with `Display`. Non-finite
+/// floats become `null` in JSON.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldValue<'a> {
+    Str(&'a str),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+}
 
-        #[cfg(feature = "log")]
-        {
-            // SAFETY: LOGGER is a static OnceLock that was just set above and will
-            // never be dropped, so the reference is valid for 'static.
-            let logger_ref: &'static Logger =
-                unsafe { &*(LOGGER.get().expect("just set") as *const Logger) };
-            log::set_logger(logger_ref).expect("log facade logger already set");
-            log::set_max_level(logger_ref.level().to_log_level_filter());
+impl std::fmt::Display for FieldValue<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Str(s) => f.write_str(s),
+            Self::I64(n) => write!(f, "{n}"),
+            Self::U64(n) => write!(f, "{n}"),
+            Self::F64(n) => write!(f, "{n}"),
+            Self::Bool(b) => write!(f, "{b}"),
         }
+    }
+}
 
-        Ok(())
+macro_rules! field_value_from {
+    ($variant:ident($target:ty): $($ty:ty),+) => {
+        $(impl From<$ty> for FieldValue<'_> {
+            fn from(value: $ty) -> Self {
+                Self::$variant(<$target>::from(value))
+            }
+        })+
+    };
+}
+
+field_value_from!(I64(i64): i8, i16, i32, i64);
+field_value_from!(U64(u64): u8, u16, u32, u64);
+field_value_from!(F64(f64): f32, f64);
+field_value_from!(Bool(bool): bool);
+
+impl From<isize> for FieldValue<'_> {
+    fn from(value: isize) -> Self {
+        Self::I64(value as i64)
     }
 }
 
-impl Default for LoggerBuilder {
-    fn default() -> Self {
-        Self::new()
+impl From<usize> for FieldValue<'_> {
+    fn from(value: usize) -> Self {
+        Self::U64(value as u64)
     }
 }
 
-/// Error returned when attempting to initialize the logger more than once.
-#[derive(Debug)]
-pub struct InitError;
+impl<'a> From<&'a str> for FieldValue<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::Str(value)
+    }
+}
 
-impl fmt::Display for InitError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "nanologger: logger already initialized")
+impl<'a> From<&'a String> for FieldValue<'a> {
+    fn from(value: &'a String) -> Self {
+        Self::Str(value)
     }
 }
 
-impl std::error::Error for InitError {}
+/// A structured key/value pair attached to a record by [`log_fields`] or
+/// [`log_kv!`].
+type Field<'a> = (&'a str, FieldValue<'a>);
 
-/// Convenience function: initialize the global logger with default settings (level = Info).
-pub fn init() -> Result<(), InitError> {
-    LoggerBuilder::new().init()
+/// Per-record optional segments of a log line. `None` omits the segment.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Segments<'a> {
+    pub(crate) sequence: Option<u64>,
+    pub(crate) timestamp: Option<&'a str>,
+    pub(crate) thread_info: Option<&'a str>,
+    pub(crate) source_loc: Option<(&'a str, u32)>,
+    /// The first `module_path` segment, shown as a `[name]` tag.
+    pub(crate) crate_tag: Option<&'a str>,
+    /// The module path (or facade target), emitted with its first segment as
+    /// `crate` and `module` keys in structured output.
+    pub(crate) module: Option<&'a str>,
+    /// From a color rule: replaces the level colors for the whole line.
+    pub(crate) line_style: Option<LineStyle>,
+    pub(crate) fields: &'a [Field<'a>],
+    /// In compact mode: timestamp and thread match the previous line, so plain
+    /// text writes spaces in their place.
+    pub(crate) repeat_meta: bool,
 }
 
-/// Changes the global log level at runtime.
+/// Core formatting function. Produces the full log line with optional sequence
+/// number, timestamp, thread info, and source location.
 ///
-/// Has no effect if the logger has not been initialized.
-pub fn set_level(level: LogLevel) {
-    if let Some(logger) = LOGGER.get() {
-        logger.level.store(level.as_u8(), Ordering::Relaxed);
+/// Format: `#{seq} {timestamp} ({thread}) {bold_colored_prefix} [{crate}] {app@version} {host} [{file}:{line}] {message_text}{line_ending}`
+/// Segments are omitted when `None`.
+#[cfg(test)]
+pub(crate) fn format_line(
+    opts: &FormatOptions,
+    level: LogLevel,
+    message: &str,
+    use_color: bool,
+    segments: &Segments<'_>,
+) -> String {
+    let mut out = String::with_capacity(estimated_len(opts, message, segments));
+    format_line_into(&mut out, opts, level, message, use_color, segments);
+    out
+}
 
-        #[cfg(feature = "log")]
-        log::set_max_level(level.to_log_level_filter());
+/// Rough upper bound on a formatted line's length, used to pre-size buffers.
+fn estimated_len(opts: &FormatOptions, message: &str, segments: &Segments<'_>) -> usize {
+    // Sequence number, tag, color codes, separators, and line ending.
+    const FIXED: usize = 48;
+    FIXED
+        + message.len()
+        + segments.timestamp.map_or(0, str::len)
+        + segments.thread_info.map_or(0, str::len)
+        + segments.source_loc.map_or(0, |(file, _)| file.len() + 12)
+        + opts.app.as_ref().map_or(0, String::len)
+        + opts.host.as_ref().map_or(0, String::len)
+        + opts.build.as_ref().map_or(0, String::len)
+}
+
+thread_local! {
+    /// Reusable per-thread buffer for formatted lines.
+    static LINE_BUFFER: std::cell::RefCell<String> = const { std::cell::RefCell::new(String::new()) };
+}
+
+/// Buffers larger than this are released after use instead of being kept.
+const LINE_BUFFER_MAX_RETAINED: usize = 64 * 1024;
+
+/// Runs `f` with an empty line buffer, reusing the thread-local one.
+///
+/// Falls back to a fresh `String` when the thread-local buffer is already in
+/// use, e.g. when an output's writer itself logs, or is being torn down.
+fn with_line_buffer(f: impl FnOnce(&mut String)) {
+    let mut f = Some(f);
+    let _ = LINE_BUFFER.try_with(|cell| {
+        if let Ok(mut buf) = cell.try_borrow_mut() {
+            buf.clear();
+            if let Some(f) = f.take() {
+                f(&mut buf);
+            }
+            if buf.capacity() > LINE_BUFFER_MAX_RETAINED {
+                *buf = String::new();
+            }
+        }
+    });
+    if let Some(f) = f {
+        f(&mut String::new());
+    }
+}
+
+/// Appends the formatted line to `out` without intermediate allocations.
+/// See [`format_line`] for the layout.
+pub(crate) fn format_line_into(
+    out: &mut String,
+    opts: &FormatOptions,
+    level: LogLevel,
+    message: &str,
+    use_color: bool,
+    segments: &Segments<'_>,
+) {
+    let eol = if opts.trailing_newline {
+        opts.line_ending.as_str()
+    } else {
+        ""
+    };
+    match opts.output_format {
+        OutputFormat::Plain => {}
+        OutputFormat::Json => {
+            format_structured_into(out, opts, level, message, segments, true);
+            out.push_str(eol);
+            return;
+        }
+        OutputFormat::Logfmt => {
+            format_structured_into(out, opts, level, message, segments, false);
+            out.push_str(eol);
+            return;
+        }
+    }
+    match opts.multiline {
+        MultilinePolicy::Split if message.contains('\n') => {
+            let mut lines = message.lines().peekable();
+            while let Some(part) = lines.next() {
+                format_plain_into(out, opts, level, part, use_color, segments);
+                if lines.peek().is_some() {
+                    out.push_str(opts.line_ending.as_str());
+                } else {
+                    out.push_str(eol);
+                }
+            }
+        }
+        MultilinePolicy::Escape if message.contains(['\n', '\r']) => {
+            let escaped = message.replace('\n', "\\n").replace('\r', "\\r");
+            format_plain_into(out, opts, level, &escaped, use_color, segments);
+            out.push_str(eol);
+        }
+        _ => {
+            format_plain_into(out, opts, level, message, use_color, segments);
+            out.push_str(eol);
+        }
+    }
+}
+
+/// Appends one plain-text line to `out`, without the line ending.
+fn format_plain_into(
+    out: &mut String,
+    opts: &FormatOptions,
+    level: LogLevel,
+    message: &str,
+    use_color: bool,
+    segments: &Segments<'_>,
+) {
+    if let Some(style) = segments
+        .line_style
+        .filter(|_| use_color && opts.color.allows_ansi())
+    {
+        // The rule's style replaces the level colors for the whole line.
+        let mut plain = String::new();
+        format_plain_into(&mut plain, opts, level, message, false, segments);
+        out.push_str(&paint_message(&plain, &style.sgr(opts.color_depth)));
+        return;
+    }
+    let start = out.len();
+    // Writing to a String cannot fail.
+    if opts.level_style == LevelStyle::SyslogNumeric {
+        let _ = write!(out, "<{}>", level.syslog_severity());
+    }
+    if let Some(seq) = segments.sequence {
+        let _ = write!(out, "#{seq:06} ");
+    }
+    let field_start = out.len();
+    match segments.timestamp {
+        Some(ts) if segments.repeat_meta => {
+            out.extend(std::iter::repeat_n(' ', ts.chars().count() + 1));
+        }
+        Some(ts) => {
+            out.push_str(ts);
+            out.push(' ');
+        }
+        None => {}
+    }
+    // The combined field keeps the timestamp and tag together, so thread
+    // info moves after it.
+    if opts.ts_level_field.is_none() {
+        push_thread_info(out, segments);
+    }
+    let paint = use_color && opts.color.allows_ansi();
+    let tag = level.tag_str();
+    if opts.level_style == LevelStyle::SyslogNumeric || !opts.show_level_tag {
+        // Already written as the line prefix, or left out.
+    } else if paint {
+        let color = opts
+            .level_colors
+            .get(level)
+            .unwrap_or(ThemeColor::Basic(level.default_color()));
+        out.push_str("\x1b[1;");
+        color.write_fg_sgr(out, opts.color_depth);
+        out.push('m');
+        out.push_str(tag);
+        out.push_str("\x1b[0m");
+        out.push(' ');
+    } else {
+        out.push_str(tag);
+        out.push(' ');
+    }
+    if let Some(field) = &opts.ts_level_field {
+        let width = visible_width(&out[field_start..]);
+        let fixed = field.tag + segments.timestamp.map_or(0, |_| field.timestamp + 1);
+        let extra = width.saturating_sub(fixed);
+        let extra = field
+            .overflow
+            .fetch_max(extra, Ordering::Relaxed)
+            .max(extra);
+        out.extend(std::iter::repeat_n(
+            ' ',
+            (fixed + extra).saturating_sub(width),
+        ));
+        push_thread_info(out, segments);
+    }
+    if let Some(name) = segments.crate_tag {
+        if paint {
+            let _ = write!(out, "\x1b[36m[{name}]\x1b[0m ");
+        } else {
+            let _ = write!(out, "[{name}] ");
+        }
+    }
+    if let Some(app) = &opts.app {
+        out.push_str(app);
+        out.push(' ');
+    }
+    if let Some(host) = opts.host.as_ref().filter(|_| !opts.host_detected) {
+        out.push_str(host);
+        out.push(' ');
+    }
+    if let Some(build) = &opts.build {
+        out.push_str(build);
+        out.push(' ');
+    }
+    if let Some((file, line)) = segments.source_loc {
+        opts.source_format.write_into(out, file, line);
+    }
+    let column = match &opts.align {
+        MessageAlign::Off => 0,
+        MessageAlign::Auto(widest) => {
+            let width = visible_width(&out[start..]);
+            widest.fetch_max(width, Ordering::Relaxed).max(width)
+        }
+        MessageAlign::Column(column) => *column,
+    };
+    if column > 0 {
+        let width = visible_width(&out[start..]);
+        out.extend(std::iter::repeat_n(' ', column.saturating_sub(width)));
+    }
+    match opts.message_color.sgr(level, opts.color_depth) {
+        Some(sgr) if paint => {
+            out.push_str(&paint_message(message, &sgr));
+        }
+        _ => out.push_str(message),
+    }
+    for (key, value) in segments.fields {
+        out.push(' ');
+        out.push_str(key);
+        out.push('=');
+        push_field_value(out, value, false);
+    }
+}
+
+/// What's wrong with a module filter entry that can't be meant as written.
+fn module_filter_entry_problem(entry: &str) -> Option<&'static str> {
+    if entry.is_empty() {
+        Some("it is empty, so it matches every module")
+    } else if entry.trim() != entry {
+        Some("it has surrounding whitespace")
+    } else if entry.split("::").any(str::is_empty) {
+        Some("it has an empty `::` segment")
+    } else if !entry.split("::").all(|segment| {
+        // `-` and `.` appear in `log` facade and custom targets, e.g.
+        // `my-crate` or `hyper.client`.
+        segment
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    }) {
+        Some("targets only contain letters, digits, `_`, `-` and `.` between `::`")
+    } else {
+        None
+    }
+}
+
+/// Appends the `(thread) ` segment, or spaces in its place in compact mode.
+fn push_thread_info(out: &mut String, segments: &Segments<'_>) {
+    match segments.thread_info {
+        Some(info) if segments.repeat_meta => {
+            out.extend(std::iter::repeat_n(' ', info.chars().count() + 3));
+        }
+        Some(info) => {
+            out.push('(');
+            out.push_str(info);
+            out.push_str(") ");
+        }
+        None => {}
+    }
+}
+
+/// Character count of `s` without ANSI escape sequences.
+fn visible_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip to the end of the CSI sequence, e.g. `\x1b[1;31m`.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+/// Appends a record as a JSON object (`json == true`) or logfmt line to `out`,
+/// without the line ending.
+fn format_structured_into(
+    out: &mut String,
+    opts: &FormatOptions,
+    level: LogLevel,
+    message: &str,
+    segments: &Segments<'_>,
+    json: bool,
+) {
+    let mut first = true;
+    let mut key = |out: &mut String, name: &str| {
+        if json {
+            out.push_str(if first { "{\"" } else { ",\"" });
+            out.push_str(name);
+            out.push_str("\":");
+        } else {
+            if !first {
+                out.push(' ');
+            }
+            out.push_str(name);
+            out.push('=');
+        }
+        first = false;
+    };
+    let quote = |out: &mut String, value: &str| {
+        if json {
+            push_json_string(out, value);
+        } else {
+            push_logfmt_value(out, value);
+        }
+    };
+    if let Some(seq) = segments.sequence {
+        key(out, "seq");
+        let _ = write!(out, "{seq}");
+    }
+    if let Some(ts) = segments.timestamp {
+        key(out, "ts");
+        quote(out, ts);
+    }
+    if let Some(thread) = segments.thread_info {
+        key(out, "thread");
+        quote(out, thread);
+    }
+    key(out, "level");
+    quote(out, level.as_str_lower());
+    if let Some(app) = &opts.app {
+        key(out, "app");
+        quote(out, app);
+    }
+    if let Some(host) = &opts.host {
+        key(out, "host");
+        quote(out, host);
+    }
+    if let Some(build) = &opts.build {
+        key(out, "build");
+        quote(out, build);
     }
+    if let Some(module) = segments.module {
+        key(out, "crate");
+        quote(out, module.split("::").next().unwrap_or(module));
+        key(out, "module");
+        quote(out, module);
+    }
+    if let Some((file, line)) = segments.source_loc {
+        key(out, "file");
+        quote(out, file);
+        key(out, "line");
+        let _ = write!(out, "{line}");
+    }
+    key(out, "msg");
+    quote(out, message);
+    for (name, value) in segments.fields {
+        key(out, name);
+        push_field_value(out, value, json);
+    }
+    if json {
+        out.push('}');
+    }
+}
+
+/// Appends a field value as JSON (`json == true`) or as a logfmt value.
+fn push_field_value(out: &mut String, value: &FieldValue<'_>, json: bool) {
+    match value {
+        FieldValue::Str(s) if json => push_json_string(out, s),
+        FieldValue::Str(s) => push_logfmt_value(out, s),
+        FieldValue::F64(n) if json && !n.is_finite() => out.push_str("null"),
+        other => {
+            let _ = write!(out, "{other}");
+        }
+    }
+}
+
+/// Appends `s` to `out` as a quoted, escaped JSON string.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Appends `s` to `out` as a logfmt value, quoted only when it contains
+/// spaces, `=`, quotes, or control characters.
+fn push_logfmt_value(out: &mut String, s: &str) {
+    let needs_quotes = s.is_empty()
+        || s.chars()
+            .any(|c| c == ' ' || c == '=' || c == '"' || c.is_control());
+    if needs_quotes {
+        push_json_string(out, s);
+    } else {
+        out.push_str(s);
+    }
+}
+
+/// Returns `true` if a message from `module_path` should be emitted given the
+/// allow and deny lists.
+///
+/// - If `allow` is empty, all modules pass the allow check.
+/// - Otherwise, `module_path` must start with at least one entry in `allow`.
+/// - After the allow check, the module is rejected if `module_path` starts with
+///   any entry in `deny`.
+///
+/// This compiles the lists on every call; build a [`ModuleFilter`] once to
+/// check many paths against the same lists.
+pub fn matches_module_filter(module_path: &str, allow: &[String], deny: &[String]) -> bool {
+    ModuleFilter::new(allow.to_vec(), deny.to_vec()).matches(module_path)
+}
+
+/// Module allow/deny lists compiled for fast prefix matching, with the same
+/// rules as [`matches_module_filter`]. The logger builds one at init (and on
+/// each SIGHUP reload), so a check walks the module path once per list instead
+/// of comparing it against every entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleFilter {
+    allow: PrefixSet,
+    deny: PrefixSet,
+    /// Entries were lowercased at build time; paths are lowercased per check.
+    case_insensitive: bool,
+}
+
+impl ModuleFilter {
+    /// Compiles an allow list (empty allows every module) and a deny list.
+    pub fn new(allow: Vec<String>, deny: Vec<String>) -> Self {
+        Self {
+            allow: PrefixSet::new(allow),
+            deny: PrefixSet::new(deny),
+            case_insensitive: false,
+        }
+    }
+
+    /// Like [`new`](Self::new), but entries match module paths regardless of
+    /// case, e.g. `myapp::db` matches a `MyApp::DB` target.
+    pub fn new_case_insensitive(allow: Vec<String>, deny: Vec<String>) -> Self {
+        let lower = |list: Vec<String>| list.iter().map(|e| e.to_lowercase()).collect();
+        Self {
+            allow: PrefixSet::new(lower(allow)),
+            deny: PrefixSet::new(lower(deny)),
+            case_insensitive: true,
+        }
+    }
+
+    /// Returns `true` if a message from `module_path` should be emitted.
+    pub fn matches(&self, module_path: &str) -> bool {
+        let lowered;
+        let module_path = if self.case_insensitive && module_path.chars().any(char::is_uppercase) {
+            lowered = module_path.to_lowercase();
+            lowered.as_str()
+        } else {
+            module_path
+        };
+        (self.allow.is_empty() || self.allow.has_prefix_of(module_path))
+            && !self.deny.has_prefix_of(module_path)
+    }
+
+    /// Parses a `NANOLOG_MODULES` spec: comma-separated module prefixes, where
+    /// a leading `-` denies the prefix and anything else allows it.
+    fn parse(spec: &str, case_insensitive: bool) -> Self {
+        let (mut allow, mut deny) = (Vec::new(), Vec::new());
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.strip_prefix('-') {
+                Some(entry) => deny.push(entry.trim().to_string()),
+                None => allow.push(entry.to_string()),
+            }
+        }
+        if case_insensitive {
+            Self::new_case_insensitive(allow, deny)
+        } else {
+            Self::new(allow, deny)
+        }
+    }
+
+    /// The filter from `NANOLOG_MODULES`, or `base` if the variable is unset.
+    /// The spec is matched with `base`'s case sensitivity.
+    fn from_env_or(base: &Self) -> Self {
+        match std::env::var("NANOLOG_MODULES") {
+            Ok(spec) => Self::parse(&spec, base.case_insensitive),
+            Err(_) => base.clone(),
+        }
+    }
+}
+
+/// A set of module prefixes stored as a radix trie, answering "does any entry
+/// start `path`?" in one walk down the path instead of a scan of every entry.
+///
+/// Entries covered by a shorter entry are dropped at build time, so every
+/// terminal node is a leaf and the walk can stop at the first one it reaches.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct PrefixSet {
+    /// Node 0 is the root; empty when the set has no entries.
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TrieNode {
+    terminal: bool,
+    /// Edges as `(label, child)`; labels are non-empty and start with
+    /// distinct bytes.
+    children: Vec<(Box<[u8]>, usize)>,
+}
+
+impl PrefixSet {
+    fn new(prefixes: Vec<String>) -> Self {
+        let mut sorted: Vec<&[u8]> = prefixes.iter().map(|p| p.as_bytes()).collect();
+        sorted.sort_unstable();
+        let mut kept: Vec<&[u8]> = Vec::with_capacity(sorted.len());
+        for prefix in sorted {
+            // Sorting places a covering prefix right before what it covers.
+            if !kept.last().is_some_and(|k| prefix.starts_with(k)) {
+                kept.push(prefix);
+            }
+        }
+        let mut set = Self::default();
+        if !kept.is_empty() {
+            set.build(&kept, 0);
+        }
+        set
+    }
+
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds the node for `group`, sorted entries that share their first
+    /// `depth` bytes, and returns its index.
+    fn build(&mut self, group: &[&[u8]], depth: usize) -> usize {
+        let id = self.nodes.len();
+        // Prefix-free input: an entry ending here is the group's only entry.
+        let terminal = group[0].len() == depth;
+        self.nodes.push(TrieNode {
+            terminal,
+            children: Vec::new(),
+        });
+        let mut rest = if terminal { &[][..] } else { group };
+        while let Some(first) = rest.first() {
+            let byte = first[depth];
+            let len = rest.iter().take_while(|e| e[depth] == byte).count();
+            let (sub, tail) = rest.split_at(len);
+            // In sorted order, the first and last entries bound the common prefix.
+            let last = sub[len - 1];
+            let common = first[depth..]
+                .iter()
+                .zip(&last[depth..])
+                .take_while(|(a, b)| a == b)
+                .count();
+            let child = self.build(sub, depth + common);
+            self.nodes[id]
+                .children
+                .push((first[depth..depth + common].into(), child));
+            rest = tail;
+        }
+        id
+    }
+
+    fn has_prefix_of(&self, path: &str) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let path = path.as_bytes();
+        let (mut node, mut pos) = (0, 0);
+        loop {
+            let TrieNode { terminal, children } = &self.nodes[node];
+            if *terminal {
+                return true;
+            }
+            let Some(&byte) = path.get(pos) else {
+                return false;
+            };
+            let Some((label, child)) = children.iter().find(|(label, _)| label[0] == byte) else {
+                return false;
+            };
+            if !path[pos..].starts_with(label) {
+                return false;
+            }
+            pos += label.len();
+            node = *child;
+        }
+    }
+}
+
+/// SIGHUP handling for [`LoggerBuilder::reload_on_sighup`]. The handler only
+/// sets a flag; the reload itself runs on the next log call.
+#[cfg(unix)]
+mod sighup {
+    use std::os::raw::c_int;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const SIGHUP: c_int = 1;
+
+    static PENDING: AtomicBool = AtomicBool::new(false);
+
+    extern "C" {
+        fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+    }
+
+    extern "C" fn on_sighup(_: c_int) {
+        PENDING.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn install() {
+        // SAFETY: `on_sighup` only stores to an atomic, which is async-signal-safe.
+        unsafe {
+            signal(SIGHUP, on_sighup);
+        }
+    }
+
+    pub(crate) fn take_pending() -> bool {
+        PENDING.load(Ordering::Relaxed) && PENDING.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[cfg(unix)]
+mod raw_fd {
+    use std::os::raw::{c_int, c_void};
+
+    extern "C" {
+        fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+    }
+
+    /// Writes to a file descriptor it doesn't own, for [`super::LogOutput::raw_fd`].
+    pub(crate) struct RawFdWriter(pub(crate) c_int);
+
+    impl std::io::Write for RawFdWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            // SAFETY: `buf` is valid for `buf.len()` bytes. An fd that isn't
+            // open makes the call fail with EBADF rather than touch memory.
+            let n = unsafe { write(self.0, buf.as_ptr().cast(), buf.len()) };
+            if n < 0 {
+                Err(std::io::Error::last_os_error())
+            } else {
+                Ok(n as usize)
+            }
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Longest line [`super::emergency`] writes, newline included.
+    pub(crate) const EMERGENCY_LINE_MAX: usize = 512;
+
+    /// Copies `tag`, a space, `message`, and a newline into a stack buffer
+    /// and writes it to stderr with a single `write(2)`. Longer messages are
+    /// cut to fit. No allocation, no locks.
+    pub(crate) fn emergency(tag: &str, message: &str) {
+        let mut line = [0u8; EMERGENCY_LINE_MAX];
+        let mut len = 0;
+        for part in [tag.as_bytes(), b" ", message.as_bytes()] {
+            let n = part.len().min(EMERGENCY_LINE_MAX - 1 - len);
+            line[len..len + n].copy_from_slice(&part[..n]);
+            len += n;
+        }
+        line[len] = b'\n';
+        // SAFETY: `line` is valid for `len + 1` bytes; fd 2 being closed only
+        // makes the call fail. The result is ignored: there's nowhere left
+        // to report a failure.
+        unsafe {
+            write(2, line.as_ptr().cast(), len + 1);
+        }
+    }
+}
+
+/// Represents a log output destination.
+///
+/// Each output carries its own level filter. [`LogOutput::term`] writes colored
+/// output to stderr while [`LogOutput::writer`] writes plain text to an
+/// arbitrary `Write` destination.
+///
+/// `LogOutput` is an opaque struct built with its constructors; use
+/// [`LogOutput::kind`] to tell which kind of destination it is:
+///
+/// ```rust
+/// use nanologger::{LogLevel, LogOutput, OutputKind};
+///
+/// assert!(matches!(LogOutput::term(LogLevel::Info).kind(), OutputKind::Term));
+/// ```
+///
+/// Timestamps and thread info default to the logger-wide settings and can be
+/// overridden per output:
+///
+/// ```rust,no_run
+/// use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+///
+/// LoggerBuilder::new()
+///     .timestamps(true)
+///     .add_output(LogOutput::term(LogLevel::Info).timestamps(false))
+///     .add_output(LogOutput::writer(LogLevel::Trace, std::io::sink()).thread_info(true))
+///     .init()
+///     .unwrap();
+/// ```
+pub struct LogOutput {
+    level: LogLevel,
+    timestamps: Option<bool>,
+    thread_info: Option<bool>,
+    /// Static `key=value` fields appended to every record on this output.
+    fields: Vec<(String, String)>,
+    /// The last line's timestamp (to the second) and thread info, for
+    /// [`LoggerBuilder::compact`], which always holds the dispatch lock.
+    last_meta: std::sync::Mutex<Option<(Option<String>, Option<String>)>>,
+    /// Replaces stderr as a `Term` output's destination.
+    term_writer: Option<std::sync::Mutex<Box<dyn Write + Send>>>,
+    /// Where a `Term` output's lines go once stderr writes fail.
+    fallback: Option<Box<LogOutput>>,
+    /// Consecutive failed stderr writes; at [`TERM_DEAD_AFTER`] the output
+    /// stops writing to stderr for good.
+    term_failures: std::sync::atomic::AtomicU32,
+    /// Extra attempts and first backoff for transient writer errors.
+    retry: Option<(u32, std::time::Duration)>,
+    /// Flush after every line.
+    flush_each_line: bool,
+    encoding: Encoding,
+    /// Whether the destination is still empty, so the next write starts with
+    /// the encoding's byte order mark.
+    at_start: std::sync::atomic::AtomicBool,
+    bytes_written: AtomicU64,
+    /// With [`LogOutput::buffered_and_file`]: also keeps each unencoded line.
+    ring: Option<std::sync::Arc<std::sync::Mutex<LineBuffer>>>,
+    sink: OutputSink,
+}
+
+/// Failed stderr writes in a row after which a `Term` output gives up on it.
+const TERM_DEAD_AFTER: u32 = 3;
+
+/// The kind of destination a [`LogOutput`] writes to, as returned by
+/// [`LogOutput::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OutputKind {
+    /// stderr, from [`LogOutput::term`].
+    Term,
+    /// A `Write` destination, from [`LogOutput::writer`], [`LogOutput::file`]
+    /// or [`LogOutput::buffered_and_file`].
+    Writer,
+    /// Several `Write` destinations, from [`LogOutput::tee`].
+    Tee,
+    /// A line sink, from [`LogOutput::test`] or [`LogOutput::test_with`].
+    Test,
+    /// An in-memory ring buffer, from [`LogOutput::buffer`].
+    Buffer,
+    /// Length-prefixed MessagePack, from [`LogOutput::msgpack`].
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+}
+
+impl OutputKind {
+    /// The variant name, as shown by `LogOutput`'s `Debug`.
+    fn name(self) -> &'static str {
+        match self {
+            OutputKind::Term => "Term",
+            OutputKind::Writer => "Writer",
+            OutputKind::Tee => "Tee",
+            OutputKind::Test => "Test",
+            OutputKind::Buffer => "Buffer",
+            #[cfg(feature = "msgpack")]
+            OutputKind::MsgPack => "MsgPack",
+        }
+    }
+}
+
+/// Where a [`LogOutput`] writes its formatted lines.
+enum OutputSink {
+    /// Logs to stderr with optional color support.
+    Term,
+    /// Logs to an arbitrary `Write` destination in plain text.
+    Writer(std::sync::Mutex<Box<dyn Write + Send>>),
+    /// Logs the same plain-text line to several `Write` destinations.
+    Tee(std::sync::Mutex<Vec<Box<dyn Write + Send>>>),
+    /// Logs plain text through a line sink; `print!()` by default, which is
+    /// captured by Rust's test harness.
+    Test(Box<dyn Fn(&str) + Send + Sync>),
+    /// Keeps recent lines in memory and forwards new ones to subscribers.
+    Buffer(std::sync::Arc<std::sync::Mutex<LineBuffer>>),
+    /// Writes length-prefixed MessagePack records instead of text lines.
+    #[cfg(feature = "msgpack")]
+    MsgPack(std::sync::Mutex<Box<dyn Write + Send>>),
+}
+
+impl LogOutput {
+    fn new(level: LogLevel, sink: OutputSink) -> Self {
+        LogOutput {
+            level,
+            timestamps: None,
+            thread_info: None,
+            fields: Vec::new(),
+            last_meta: std::sync::Mutex::new(None),
+            term_writer: None,
+            fallback: None,
+            term_failures: std::sync::atomic::AtomicU32::new(0),
+            retry: None,
+            flush_each_line: false,
+            encoding: Encoding::Utf8,
+            at_start: std::sync::atomic::AtomicBool::new(true),
+            bytes_written: AtomicU64::new(0),
+            ring: None,
+            sink,
+        }
+    }
+
+    /// Returns which kind of destination this output writes to.
+    pub fn kind(&self) -> OutputKind {
+        match &self.sink {
+            OutputSink::Term => OutputKind::Term,
+            OutputSink::Writer(_) => OutputKind::Writer,
+            OutputSink::Tee(_) => OutputKind::Tee,
+            OutputSink::Test(_) => OutputKind::Test,
+            OutputSink::Buffer(_) => OutputKind::Buffer,
+            #[cfg(feature = "msgpack")]
+            OutputSink::MsgPack(_) => OutputKind::MsgPack,
+        }
+    }
+
+    /// Creates a `Term` output that writes to stderr at the given level.
+    pub fn term(level: LogLevel) -> Self {
+        LogOutput::new(level, OutputSink::Term)
+    }
+
+    /// Creates a `Writer` output that writes plain text to the given destination.
+    pub fn writer(level: LogLevel, w: impl Write + Send + 'static) -> Self {
+        LogOutput::from_boxed_writer(level, Box::new(w))
+    }
+
+    /// Creates a `Writer` output from an already boxed writer, e.g. one chosen
+    /// at runtime:
+    ///
+    /// ```rust
+    /// use nanologger::{LogLevel, LogOutput};
+    /// use std::io::Write;
+    ///
+    /// let to_stdout = true;
+    /// let w: Box<dyn Write + Send> = if to_stdout {
+    ///     Box::new(std::io::stdout())
+    /// } else {
+    ///     Box::new(std::io::sink())
+    /// };
+    /// let output = LogOutput::from_boxed_writer(LogLevel::Info, w);
+    /// ```
+    pub fn from_boxed_writer(level: LogLevel, w: Box<dyn Write + Send>) -> Self {
+        LogOutput::new(level, OutputSink::Writer(std::sync::Mutex::new(w)))
+    }
+
+    /// Creates an output that writes each plain-text line to every writer in
+    /// `writers`, in order.
+    ///
+    /// The line is formatted and filtered once for all of them, which is
+    /// cheaper than adding one [`LogOutput::writer`] per destination. An error
+    /// from one writer does not stop the others.
+    pub fn tee(level: LogLevel, writers: Vec<Box<dyn Write + Send>>) -> Self {
+        LogOutput::new(level, OutputSink::Tee(std::sync::Mutex::new(writers)))
+    }
+
+    /// Creates a `Writer` output that appends plain text to the file at `path`,
+    /// creating it if it does not exist.
+    ///
+    /// The path is taken as [`AsRef<Path>`](std::path::Path), so non-UTF-8
+    /// paths work on platforms that allow them.
+    pub fn file(level: LogLevel, path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let empty = file.metadata().map_or(true, |meta| meta.len() == 0);
+        let output = LogOutput::writer(level, file);
+        output.at_start.store(empty, Ordering::Relaxed);
+        Ok(output)
+    }
+
+    /// Creates a `Writer` output that writes plain text to an inherited file
+    /// descriptor, e.g. fd 3 handed over by a supervisor when stderr is
+    /// reserved for something else. Unix only.
+    ///
+    /// The descriptor is borrowed: it is never closed, and writes to a
+    /// descriptor that isn't open simply fail. To hand ownership over so the
+    /// descriptor closes with the output, use [`owned_fd`](Self::owned_fd).
+    #[cfg(unix)]
+    pub fn raw_fd(level: LogLevel, fd: std::os::unix::io::RawFd) -> Self {
+        LogOutput::writer(level, raw_fd::RawFdWriter(fd))
+    }
+
+    /// Like [`raw_fd`](Self::raw_fd), but takes ownership of the descriptor.
+    /// Unix only.
+    #[cfg(unix)]
+    pub fn owned_fd(level: LogLevel, fd: std::os::unix::io::OwnedFd) -> Self {
+        LogOutput::writer(level, std::fs::File::from(fd))
+    }
+
+    /// Creates a `Test` output that writes plain text via `print!()`.
+    /// Output is captured by Rust's test harness, or by
+    /// `capture_test_output` with the `test-util` feature.
+    pub fn test(level: LogLevel) -> Self {
+        LogOutput::test_with(level, |line| {
+            #[cfg(feature = "test-util")]
+            if test_capture::push(line) {
+                return;
+            }
+            print!("{line}")
+        })
+    }
+
+    /// Creates a `Test` output that hands each formatted plain-text line
+    /// (including its line ending) to `f` instead of `print!()`.
+    ///
+    /// Useful when a harness captures output somewhere other than stdout.
+    pub fn test_with(level: LogLevel, f: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        LogOutput::new(level, OutputSink::Test(Box::new(f)))
+    }
+
+    /// Creates an output that keeps the last `capacity` lines in memory, plus
+    /// a [`BufferHandle`] to read them back or [subscribe](BufferHandle::subscribe)
+    /// to new ones, e.g. for a live log view in an embedded dashboard.
+    ///
+    /// Lines are stored without their line ending.
+    pub fn buffer(level: LogLevel, capacity: usize) -> (Self, BufferHandle) {
+        let shared = LineBuffer::shared(capacity);
+        (
+            LogOutput::new(level, OutputSink::Buffer(shared.clone())),
+            BufferHandle(shared),
+        )
+    }
+
+    /// Combines [`buffer`](Self::buffer) and [`file`](Self::file): each line
+    /// is formatted once, kept in a ring of the last `capacity` lines, and
+    /// appended to the file at `path`. Handy for a "last N lines" admin
+    /// endpoint next to persistent disk logs.
+    ///
+    /// An [`encoding`](Self::encoding) applies to the file only; the ring
+    /// always keeps UTF-8 lines.
+    pub fn buffered_and_file(
+        level: LogLevel,
+        capacity: usize,
+        path: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<(Self, BufferHandle)> {
+        let shared = LineBuffer::shared(capacity);
+        let mut output = LogOutput::file(level, path)?;
+        output.ring = Some(shared.clone());
+        Ok((output, BufferHandle(shared)))
+    }
+
+    /// Creates an output that writes each record to `w` as a length-prefixed
+    /// MessagePack map instead of a text line. See [`msgpack`] for the schema.
+    #[cfg(feature = "msgpack")]
+    pub fn msgpack(level: LogLevel, w: impl Write + Send + 'static) -> Self {
+        LogOutput::new(
+            level,
+            OutputSink::MsgPack(std::sync::Mutex::new(Box::new(w))),
+        )
+    }
+
+    /// Enables or disables timestamps for this output only, overriding
+    /// [`LoggerBuilder::timestamps`].
+    pub fn timestamps(mut self, enabled: bool) -> Self {
+        self.timestamps = Some(enabled);
+        self
+    }
+
+    /// Enables or disables thread info for this output only, overriding
+    /// [`LoggerBuilder::thread_info`].
+    pub fn thread_info(mut self, enabled: bool) -> Self {
+        self.thread_info = Some(enabled);
+        self
+    }
+
+    /// Sends a [`term`](Self::term) output's lines to `w` instead of stderr,
+    /// e.g. a GUI console widget that parses ANSI codes. Lines stay colored
+    /// (unless colors are globally disabled), since there's no terminal to
+    /// check. Has no effect on other output kinds.
+    pub fn term_writer(mut self, w: Box<dyn Write + Send>) -> Self {
+        self.term_writer = Some(std::sync::Mutex::new(w));
+        self
+    }
+
+    /// Sets where a [`term`](Self::term) output's lines go when writing to
+    /// stderr fails, e.g. because a supervisor closed the pipe. Each failed
+    /// line is written to `output` instead. After a few failures in a row,
+    /// stderr is abandoned for good, with a one-time note to `output`, so a
+    /// dead stderr isn't retried on every call. Without a fallback, lines
+    /// written while stderr is dead are dropped.
+    ///
+    /// The fallback's own level filter is not applied. Has no effect on other
+    /// output kinds.
+    pub fn fallback(mut self, output: LogOutput) -> Self {
+        self.fallback = Some(Box::new(output));
+        self
+    }
+
+    /// Retries a write that fails with
+    /// [`Interrupted`](std::io::ErrorKind::Interrupted) or
+    /// [`WouldBlock`](std::io::ErrorKind::WouldBlock) up to `attempts` more
+    /// times in a row, sleeping `backoff` before the first retry and doubling
+    /// it each time, e.g. for busy non-blocking sockets. Bytes already
+    /// written are not repeated, and progress resets the count. Other errors,
+    /// and transient ones that outlast the retries, drop the line as usual.
+    /// Off by default.
+    ///
+    /// Applies to `writer`, `file`, `tee`, and `msgpack` outputs.
+    pub fn retry_transient(mut self, attempts: u32, backoff: std::time::Duration) -> Self {
+        self.retry = Some((attempts, backoff));
+        self
+    }
+
+    /// Sets how `writer`, `file`, and `tee` outputs encode lines:
+    /// [`Encoding::Utf8`] (the default), `Utf8Bom`, or `Utf16Le`. The byte
+    /// order mark, if any, is written once before the first line, and only if
+    /// a `file` output's file was empty. [`bytes_written`](Self::bytes_written)
+    /// counts encoded bytes. Has no effect on other output kinds.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Flushes this output after every line. For a `test` output this flushes
+    /// stdout, so runners such as nextest that buffer `print!` output don't
+    /// interleave lines from parallel tests. Off by default.
+    pub fn flush_each_line(mut self, enabled: bool) -> Self {
+        self.flush_each_line = enabled;
+        self
+    }
+
+    /// Adds a static field to every record written to this output only, e.g.
+    /// `env=prod` on a file but not the terminal. Static fields follow the
+    /// record's own fields: trailing `key=value` pairs in text, top-level keys
+    /// in JSON.
+    pub fn with_field(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((key.into(), value.into()));
+        self
+    }
+
+    /// Returns this output's level filter.
+    pub fn level(&self) -> LogLevel {
+        self.level
+    }
+
+    /// Returns the number of bytes this output has written successfully.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    /// Writes an already formatted line. Write errors are silently ignored;
+    /// only successful writes count toward [`bytes_written`](Self::bytes_written).
+    fn write_line(&self, line: &str) {
+        if let Some(ring) = &self.ring {
+            let mut buffer = ring.lock().unwrap_or_else(|e| e.into_inner());
+            buffer.push(line.trim_end_matches(['\n', '\r']));
+        }
+        let written = match &self.sink {
+            OutputSink::Term => return self.write_term(line),
+            OutputSink::Writer(writer) => {
+                let mut w = lock_writer(writer);
+                let bytes = self.encode(line);
+                let ok = self.write_retrying(&mut **w, &bytes).is_ok();
+                return self.count_written(ok, &bytes);
+            }
+            #[cfg(feature = "msgpack")]
+            OutputSink::MsgPack(_) => return self.write_bytes(line.as_bytes()),
+            // Counted once per line if any writer accepted it.
+            OutputSink::Tee(writers) => {
+                let mut writers = lock_writer(writers);
+                let bytes = self.encode(line);
+                let mut any_ok = false;
+                for w in writers.iter_mut() {
+                    any_ok |= self.write_retrying(&mut **w, &bytes).is_ok();
+                }
+                return self.count_written(any_ok, &bytes);
+            }
+            OutputSink::Test(sink) => {
+                sink(line);
+                true
+            }
+            OutputSink::Buffer(shared) => {
+                let mut buffer = shared.lock().unwrap_or_else(|e| e.into_inner());
+                buffer.push(line.trim_end_matches(['\n', '\r']));
+                true
+            }
+        };
+        if written {
+            self.bytes_written
+                .fetch_add(line.len() as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Encodes `line` with this output's [`Encoding`], starting with the byte
+    /// order mark if nothing was written yet. Callers hold the writer lock.
+    fn encode<'a>(&self, line: &'a str) -> std::borrow::Cow<'a, [u8]> {
+        let bom = self.encoding != Encoding::Utf8 && self.at_start.load(Ordering::Relaxed);
+        self.encoding.encode(line, bom)
+    }
+
+    /// Counts `bytes` toward [`bytes_written`](Self::bytes_written) if they
+    /// were `written`, after which no byte order mark is due.
+    fn count_written(&self, written: bool, bytes: &[u8]) {
+        if written {
+            self.at_start.store(false, Ordering::Relaxed);
+            self.bytes_written
+                .fetch_add(bytes.len() as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Like `write_all`, but with the [`retry_transient`](Self::retry_transient)
+    /// policy for `Interrupted` and `WouldBlock` errors.
+    fn write_retrying(&self, w: &mut dyn Write, mut bytes: &[u8]) -> std::io::Result<()> {
+        let Some((attempts, backoff)) = self.retry else {
+            return w.write_all(bytes);
+        };
+        let mut retries = 0;
+        while !bytes.is_empty() {
+            match w.write(bytes) {
+                Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    bytes = &bytes[n..];
+                    retries = 0;
+                }
+                Err(e)
+                    if retries < attempts
+                        && matches!(
+                            e.kind(),
+                            std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock
+                        ) =>
+                {
+                    std::thread::sleep(backoff.saturating_mul(1 << retries.min(16)));
+                    retries += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes to stderr (or the [`term_writer`](Self::term_writer)),
+    /// diverting to the fallback when that fails.
+    fn write_term(&self, line: &str) {
+        let failures = &self.term_failures;
+        if failures.load(Ordering::Relaxed) < TERM_DEAD_AFTER {
+            let written = match &self.term_writer {
+                Some(writer) => lock_writer(writer).write_all(line.as_bytes()).is_ok(),
+                None => std::io::stderr().lock().write_all(line.as_bytes()).is_ok(),
+            };
+            if written {
+                failures.store(0, Ordering::Relaxed);
+                self.bytes_written
+                    .fetch_add(line.len() as u64, Ordering::Relaxed);
+                return;
+            }
+            if failures.fetch_add(1, Ordering::Relaxed) + 1 == TERM_DEAD_AFTER {
+                if let Some(fallback) = &self.fallback {
+                    fallback.write_line(&format!(
+                        "nanologger: stderr failed {TERM_DEAD_AFTER} writes in a row; \
+                         logging here from now on{}",
+                        line_ending_of(line)
+                    ));
+                }
+            }
+        }
+        if let Some(fallback) = &self.fallback {
+            fallback.write_line(line);
+        }
+    }
+
+    /// Whether the timestamp (to the second) and thread info in `segments`
+    /// match this output's previous line. Remembers them for the next call.
+    fn repeats_meta(&self, segments: &Segments<'_>) -> bool {
+        let current = (
+            segments
+                .timestamp
+                .map(|ts| ts.rsplit_once('.').map_or(ts, |(secs, _)| secs)),
+            segments.thread_info,
+        );
+        let mut last = self.last_meta.lock().unwrap_or_else(|e| e.into_inner());
+        let repeat = last
+            .as_ref()
+            .is_some_and(|(ts, thread)| (ts.as_deref(), thread.as_deref()) == current);
+        if !repeat {
+            *last = Some((current.0.map(str::to_string), current.1.map(str::to_string)));
+        }
+        repeat
+    }
+
+    /// Writes an encoded binary record to a MessagePack output.
+    #[cfg(feature = "msgpack")]
+    fn write_bytes(&self, bytes: &[u8]) {
+        let OutputSink::MsgPack(writer) = &self.sink else {
+            return;
+        };
+        let written = self
+            .write_retrying(&mut **lock_writer(writer), bytes)
+            .is_ok();
+        if written {
+            self.bytes_written
+                .fetch_add(bytes.len() as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Flushes the underlying destination. Errors are silently ignored.
+    fn flush(&self) {
+        match &self.sink {
+            OutputSink::Term => {
+                if self.term_failures.load(Ordering::Relaxed) < TERM_DEAD_AFTER {
+                    match &self.term_writer {
+                        Some(writer) => {
+                            let _ = lock_writer(writer).flush();
+                        }
+                        None => {
+                            let _ = std::io::stderr().flush();
+                        }
+                    }
+                }
+                if let Some(fallback) = &self.fallback {
+                    fallback.flush();
+                }
+            }
+            OutputSink::Writer(writer) => {
+                let _ = lock_writer(writer).flush();
+            }
+            #[cfg(feature = "msgpack")]
+            OutputSink::MsgPack(writer) => {
+                let _ = lock_writer(writer).flush();
+            }
+            OutputSink::Tee(writers) => {
+                for w in lock_writer(writers).iter_mut() {
+                    let _ = w.flush();
+                }
+            }
+            OutputSink::Test(_) => {
+                let _ = std::io::stdout().flush();
+            }
+            OutputSink::Buffer(_) => {}
+        }
+    }
+}
+
+/// Shows the kind of output and its level, e.g. `Writer { level: Trace }`,
+/// plus its fallback if one is set. Writers and sinks stay opaque.
+impl std::fmt::Debug for LogOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = f.debug_struct(self.kind().name());
+        out.field("level", &self.level);
+        if let Some(fallback) = &self.fallback {
+            out.field("fallback", fallback);
+        }
+        out.finish()
+    }
+}
+
+/// Locks an output's writer, recovering it if another thread panicked while
+/// holding the lock, so one unrelated panic doesn't silence the output.
+fn lock_writer<T: ?Sized>(writer: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    writer.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The line ending at the end of `line`, if any.
+fn line_ending_of(line: &str) -> &str {
+    let body = line.trim_end_matches(['\n', '\r']);
+    &line[body.len()..]
+}
+
+/// Shared state behind a [`LogOutput::buffer`] output and its [`BufferHandle`].
+struct LineBuffer {
+    capacity: usize,
+    lines: std::collections::VecDeque<String>,
+    subscribers: Vec<std::sync::mpsc::SyncSender<String>>,
+}
+
+impl LineBuffer {
+    fn shared(capacity: usize) -> std::sync::Arc<std::sync::Mutex<LineBuffer>> {
+        std::sync::Arc::new(std::sync::Mutex::new(LineBuffer {
+            capacity,
+            lines: std::collections::VecDeque::with_capacity(capacity),
+            subscribers: Vec::new(),
+        }))
+    }
+
+    fn push(&mut self, line: &str) {
+        // A subscriber that is behind misses lines; one that hung up is removed.
+        self.subscribers.retain(|tx| {
+            !matches!(
+                tx.try_send(line.to_string()),
+                Err(std::sync::mpsc::TrySendError::Disconnected(_))
+            )
+        });
+        if self.capacity == 0 {
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line.to_string());
+    }
+}
+
+/// Reads the lines kept by a [`LogOutput::buffer`] output. Cheap to clone.
+#[derive(Clone)]
+pub struct BufferHandle(std::sync::Arc<std::sync::Mutex<LineBuffer>>);
+
+impl BufferHandle {
+    /// Returns the buffered lines, oldest first.
+    pub fn lines(&self) -> Vec<String> {
+        let buffer = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        buffer.lines.iter().cloned().collect()
+    }
+
+    /// Returns a receiver that gets every line logged from now on. Up to the
+    /// buffer's capacity (at least one) lines queue up per subscriber; a
+    /// subscriber that falls further behind misses lines, without affecting
+    /// the buffer or other subscribers. Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> std::sync::mpsc::Receiver<String> {
+        let mut buffer = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let (tx, rx) = std::sync::mpsc::sync_channel(buffer.capacity.max(1));
+        buffer.subscribers.push(tx);
+        rx
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Global Logger
+// ---------------------------------------------------------------------------
+
+/// The global logger. Immutable after initialization.
+/// The global logger. Immutable after initialization.
+pub struct Logger {
+    level: AtomicU8,
+    level_match: LevelMatch,
+    timestamps: bool,
+    clock: Option<Clock>,
+    /// Minutes east of UTC for timestamps; `None` uses local time.
+    utc_offset: Option<i32>,
+    /// Least severe level that gets a generated timestamp.
+    timestamp_from: LogLevel,
+    source_location: bool,
+    thread_info: bool,
+    thread_id_style: ThreadIdStyle,
+    sequence_numbers: bool,
+    sequence: AtomicU64,
+    /// The active module filter; replaced wholesale on a SIGHUP reload.
+    modules: std::sync::RwLock<ModuleFilter>,
+    /// The builder's module filter, restored when `NANOLOG_MODULES` is unset.
+    base_modules: ModuleFilter,
+    reload_on_sighup: bool,
+    format: FormatOptions,
+    marker_level: LogLevel,
+    summary_on_shutdown: bool,
+    audit_output: Option<LogOutput>,
+    skip_empty: bool,
+    flush_on: Option<LogLevel>,
+    rate_limit: Option<RateLimiter>,
+    throttle: Option<Throttle>,
+    transform: Option<MessageTransform>,
+    color_rule: Option<ColorRule>,
+    /// Held across a whole dispatch in [`DispatchOrdering::Strict`] mode.
+    dispatch_lock: Option<std::sync::Mutex<()>>,
+    compact: bool,
+    /// Records dispatched per level, indexed by [`LogLevel::as_u8`].
+    counts: [AtomicU64; 5],
+    /// The timer thread started by [`LoggerBuilder::metrics_reporter`].
+    reporter: std::sync::Mutex<Option<MetricsReporter>>,
+    outputs: Vec<LogOutput>,
+}
+
+/// A bundle of builder settings for [`LoggerBuilder::preset`].
+///
+/// Both presets log to stderr only and honor `NANOLOGGER_LEVEL`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// `Trace` level, timestamps, and source locations as colored plain
+    /// text (colors follow the usual TTY detection).
+    Development,
+    /// `Info` level, [`OutputFormat::Json`] lines (never colored), and UTC
+    /// timestamps. The explicit format overrides `NANOLOG_FORMAT`.
+    Production,
+}
+
+/// How thread info renders threads that have no name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThreadIdStyle {
+    /// The `Debug` form of [`std::thread::ThreadId`], e.g. `ThreadId(7)` (the default).
+    #[default]
+    Debug,
+    /// A plain integer, e.g. `7`. Numbers are assigned by nanologger in the
+    /// order threads first log, starting at 1, and stay fixed for the life of
+    /// the process, independent of the standard library's `Debug` format.
+    Numeric,
+}
+
+/// Source of [`ThreadIdStyle::Numeric`] thread numbers.
+static NEXT_THREAD_NUMBER: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// This thread's [`ThreadIdStyle::Numeric`] number, assigned on first use.
+    static THREAD_NUMBER: u64 = NEXT_THREAD_NUMBER.fetch_add(1, Ordering::Relaxed);
+}
+
+/// What makes two records "the same" for [`LoggerBuilder::throttle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleKey {
+    /// The message text alone.
+    Message,
+    /// The message text and the module it was logged from.
+    MessageAndModule,
+}
+
+/// Most distinct keys [`LoggerBuilder::throttle`] tracks at once; the least
+/// recently seen key is forgotten first.
+const THROTTLE_CAPACITY: usize = 1024;
+
+/// Per-key suppression state for [`LoggerBuilder::throttle`].
+struct Throttle {
+    window: std::time::Duration,
+    key: ThrottleKey,
+    entries: std::sync::Mutex<ThrottleEntries>,
+}
+
+#[derive(Default)]
+struct ThrottleEntries {
+    map: std::collections::HashMap<(String, String), ThrottleEntry>,
+    /// Incremented on every lookup; orders entries by recency.
+    clock: u64,
+}
+
+struct ThrottleEntry {
+    emitted_at: std::time::Instant,
+    suppressed: u64,
+    last_seen: u64,
+}
+
+/// What [`Throttle::check`] decided for a record.
+enum ThrottleDecision {
+    Emit,
+    /// Emit, after a note that `n` earlier repeats were suppressed.
+    EmitAfter(u64),
+    Suppress,
+}
+
+impl Throttle {
+    fn check(&self, record: &Record<'_>) -> ThrottleDecision {
+        let module = match self.key {
+            ThrottleKey::Message => "",
+            ThrottleKey::MessageAndModule => record.module_path,
+        };
+        let now = std::time::Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.clock += 1;
+        let tick = entries.clock;
+        let key = (record.message.to_string(), module.to_string());
+        if let Some(entry) = entries.map.get_mut(&key) {
+            entry.last_seen = tick;
+            if now.duration_since(entry.emitted_at) < self.window {
+                entry.suppressed += 1;
+                return ThrottleDecision::Suppress;
+            }
+            entry.emitted_at = now;
+            return match std::mem::take(&mut entry.suppressed) {
+                0 => ThrottleDecision::Emit,
+                n => ThrottleDecision::EmitAfter(n),
+            };
+        }
+        if entries.map.len() >= THROTTLE_CAPACITY {
+            let oldest = entries
+                .map
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_seen)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.map.remove(&oldest);
+            }
+        }
+        entries.map.insert(
+            key,
+            ThrottleEntry {
+                emitted_at: now,
+                suppressed: 0,
+                last_seen: tick,
+            },
+        );
+        ThrottleDecision::Emit
+    }
+}
+
+/// How records from concurrent threads are ordered across outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatchOrdering {
+    /// One record is written to all outputs before the next starts, so every
+    /// output sees records in the same order and lines never interleave.
+    Strict,
+    /// Each output serializes its own writes only. Higher throughput with many
+    /// threads, but two outputs may see concurrent records in different orders.
+    PerOutput,
+}
+
+thread_local! {
+    /// Whether this thread holds the [`DispatchOrdering::Strict`] lock.
+    static DISPATCH_LOCK_HELD: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Releases the strict dispatch lock and clears [`DISPATCH_LOCK_HELD`].
+struct StrictGuard<'a> {
+    _lock: std::sync::MutexGuard<'a, ()>,
+}
+
+impl Drop for StrictGuard<'_> {
+    fn drop(&mut self) {
+        DISPATCH_LOCK_HELD.with(|held| held.set(false));
+    }
+}
+
+/// A record on its way to the outputs, after filtering.
+#[derive(Clone, Copy)]
+struct Record<'a> {
+    level: LogLevel,
+    message: &'a str,
+    module_path: &'a str,
+    file: &'a str,
+    line: u32,
+    fields: &'a [Field<'a>],
+    /// Replaces the clock's timestamp, for [`log_at`].
+    timestamp: Option<&'a str>,
+}
+
+/// A caller-supplied replacement for the real clock. Returns the timestamp text.
+type Clock = Box<dyn Fn() -> String + Send + Sync>;
+
+/// Rewrites a message before formatting; see [`LoggerBuilder::message_transform`].
+type MessageTransform = Box<dyn for<'a> Fn(&'a str) -> std::borrow::Cow<'a, str> + Send + Sync>;
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Formats the current local time as `HH:MM:SS.mmm` using nanotime.
+fn format_current_timestamp() -> String {
+    nanotime::NanoTime::now().to_string()
+}
+
+/// Formats the current UTC time shifted by `offset_minutes`.
+fn format_offset_timestamp(offset_minutes: i32) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    offset_timestamp(now.as_nanos(), offset_minutes)
+}
+
+/// Formats `epoch_nanos` (UTC) shifted by `offset_minutes`.
+fn offset_timestamp(epoch_nanos: u128, offset_minutes: i32) -> String {
+    let shifted = epoch_nanos as i128 + i128::from(offset_minutes) * 60_000_000_000;
+    nanotime::NanoTime::from_epoch_nanos(shifted.max(0) as u128).to_string()
+}
+
+impl Logger {
+    /// Returns the configured log level.
+    ///
+    /// Pairs with the `Release` store in [`set_level`]: once a thread observes a
+    /// new level, it also observes everything the setter did before the change.
+    pub fn level(&self) -> LogLevel {
+        LogLevel::from_u8(self.level.load(Ordering::Acquire)).unwrap_or(LogLevel::Info)
+    }
+
+    /// Describes this logger's configuration for [`LoggerBuilder::log_header`].
+    fn header(&self) -> String {
+        format!(
+            "{} {} level={} level_match={:?} timestamps={} source_location={} thread_info={} sequence_numbers={} format={}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            self.level(),
+            self.level_match,
+            self.timestamps,
+            self.source_location,
+            self.thread_info,
+            self.sequence_numbers,
+            self.format.output_format.as_str(),
+        )
+    }
+
+    /// Returns the level used by the global gate on the calling thread: the
+    /// thread's override from [`set_thread_level`] if set, else [`level`](Self::level).
+    fn effective_level(&self) -> LogLevel {
+        thread_level().unwrap_or_else(|| self.level())
+    }
+
+    /// Applies the module filter, first picking up a pending SIGHUP reload.
+    fn module_allowed(&self, module_path: &str) -> bool {
+        #[cfg(unix)]
+        if self.reload_on_sighup && sighup::take_pending() {
+            let filter = ModuleFilter::from_env_or(&self.base_modules);
+            *self.modules.write().unwrap_or_else(|e| e.into_inner()) = filter;
+        }
+        self.modules
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .matches(module_path)
+    }
+
+    /// Applies the global level gate and module filter, then dispatches.
+    ///
+    /// `filter_path` is what the module filter checks: the module path for
+    /// macros, the target for the `log` facade. The facade calls [`allows`]
+    /// and [`dispatch`] itself so it can skip formatting filtered records;
+    /// either way a record takes the same path to the outputs.
+    ///
+    /// [`allows`]: Self::allows
+    /// [`dispatch`]: Self::dispatch
+    fn submit(&self, record: &Record<'_>, filter_path: &str) {
+        if self.allows(record.level, filter_path) {
+            self.dispatch(record);
+        }
+    }
+
+    /// Whether a record at `level` from `filter_path` passes the global level
+    /// gate and the module filter.
+    fn allows(&self, level: LogLevel, filter_path: &str) -> bool {
+        self.level_match.allows(level, self.effective_level()) && self.module_allowed(filter_path)
+    }
+
+    /// Writes the [`log_summary`] line to every output, bypassing level filters.
+    fn log_summary(&self) {
+        let (level, message) = format_summary(&self.stats());
+        self.dispatch_to(
+            &self.outputs,
+            false,
+            &Record {
+                level,
+                message: &message,
+                module_path: "nanologger",
+                file: file!(),
+                line: line!(),
+                fields: &[],
+                timestamp: None,
+            },
+        );
+    }
+
+    /// Returns a snapshot of the per-level record counts.
+    fn stats(&self) -> LogStats {
+        LogStats {
+            counts: std::array::from_fn(|i| self.counts[i].load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Flushes every output, including the audit output. Write errors are
+    /// silently ignored.
+    fn flush_outputs(&self) {
+        for output in self.outputs.iter().chain(&self.audit_output) {
+            output.flush();
+        }
+    }
+
+    /// Writes a message that already passed the global level gate and module
+    /// filter to every output whose own level filter accepts it.
+    ///
+    /// Shared by the log macros and the `log` facade backend.
+    fn dispatch(&self, record: &Record<'_>) {
+        if self.skip_empty && record.message.trim().is_empty() {
+            return;
+        }
+        if let Some(throttle) = &self.throttle {
+            match throttle.check(record) {
+                ThrottleDecision::Emit => {}
+                ThrottleDecision::Suppress => return,
+                ThrottleDecision::EmitAfter(n) => {
+                    let message = format!("suppressed {n} repeats of {:?}", record.message);
+                    self.dispatch_to(
+                        &self.outputs,
+                        true,
+                        &Record {
+                            message: &message,
+                            fields: &[],
+                            ..*record
+                        },
+                    );
+                }
+            }
+        }
+        if let Some(limiter) = &self.rate_limit {
+            if !limiter.try_acquire() {
+                return;
+            }
+            if let Some(dropped) = limiter.take_summary() {
+                let message = format!("dropped {dropped} lines due to rate limit");
+                self.dispatch_to(
+                    &self.outputs,
+                    true,
+                    &Record {
+                        level: LogLevel::Warn,
+                        message: &message,
+                        module_path: "nanologger",
+                        file: file!(),
+                        line: line!(),
+                        fields: &[],
+                        timestamp: None,
+                    },
+                );
+            }
+        }
+        self.counts[record.level.as_u8() as usize].fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "test-util")]
+        test_capture::observe(record.level, record.message);
+        match &self.transform {
+            Some(transform) => {
+                let message = transform(record.message);
+                self.dispatch_to(
+                    &self.outputs,
+                    true,
+                    &Record {
+                        message: &message,
+                        ..*record
+                    },
+                );
+            }
+            None => self.dispatch_to(&self.outputs, true, record),
+        }
+        if self
+            .flush_on
+            .is_some_and(|threshold| record.level <= threshold)
+        {
+            self.flush_outputs();
+        }
+    }
+
+    /// Writes a record to `outputs`, skipping those whose level filter rejects
+    /// it when `filter_levels` is set.
+    fn dispatch_to(&self, outputs: &[LogOutput], filter_levels: bool, record: &Record<'_>) {
+        // A nested dispatch from inside an output already holds the lock.
+        let _strict = match &self.dispatch_lock {
+            Some(lock) if !DISPATCH_LOCK_HELD.with(Cell::get) => {
+                let guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+                DISPATCH_LOCK_HELD.with(|held| held.set(true));
+                Some(StrictGuard { _lock: guard })
+            }
+            _ => None,
+        };
+        let Record {
+            level,
+            message,
+            module_path,
+            file,
+            line,
+            fields,
+            timestamp,
+        } = *record;
+        // Computed at most once so every output sees the same values.
+        let timed = timestamp.is_some() || level <= self.timestamp_from;
+        let ts = if timed && outputs.iter().any(|o| self.wants_timestamps(o)) {
+            Some(match (timestamp, &self.clock, self.utc_offset) {
+                (Some(timestamp), _, _) => timestamp.to_string(),
+                (None, Some(clock), _) => clock(),
+                (None, None, Some(offset)) => format_offset_timestamp(offset),
+                (None, None, None) => format_current_timestamp(),
+            })
+        } else {
+            None
+        };
+
+        let source_loc = if self.source_location {
+            Some((file, line))
+        } else {
+            None
+        };
+
+        let thread_info_str = if outputs.iter().any(|o| self.wants_thread_info(o)) {
+            let current = std::thread::current();
+            let info = match current.name() {
+                Some(name) => name.to_string(),
+                None => match self.thread_id_style {
+                    ThreadIdStyle::Debug => format!("{:?}", current.id()),
+                    ThreadIdStyle::Numeric => THREAD_NUMBER.with(|n| n.to_string()),
+                },
+            };
+            Some(info)
+        } else {
+            None
+        };
+
+        let sequence = if self.sequence_numbers {
+            Some(self.sequence.fetch_add(1, Ordering::Relaxed) + 1)
+        } else {
+            None
+        };
+        // Asked at most once, and only if a colored output needs it.
+        let mut line_style: Option<Option<LineStyle>> = None;
+
+        for output in outputs {
+            if filter_levels && !self.level_match.allows(level, output.level) {
+                continue;
+            }
+            let with_static: Vec<Field<'_>>;
+            let fields = if output.fields.is_empty() {
+                fields
+            } else {
+                with_static = fields
+                    .iter()
+                    .copied()
+                    .chain(
+                        output
+                            .fields
+                            .iter()
+                            .map(|(k, v)| (k.as_str(), FieldValue::Str(v))),
+                    )
+                    .collect();
+                &with_static
+            };
+            let use_color = match output.sink {
+                OutputSink::Term => match self.format.color {
+                    ColorChoice::Always => true,
+                    ColorChoice::Never => false,
+                    ColorChoice::Auto => {
+                        output.term_writer.is_some() || std::io::stderr().is_terminal()
+                    }
+                },
+                OutputSink::Writer(_)
+                | OutputSink::Tee(_)
+                | OutputSink::Test(_)
+                | OutputSink::Buffer(_) => false,
+                #[cfg(feature = "msgpack")]
+                OutputSink::MsgPack(_) => {
+                    let segments = Segments {
+                        sequence,
+                        timestamp: ts.as_deref().filter(|_| self.wants_timestamps(output)),
+                        thread_info: thread_info_str
+                            .as_deref()
+                            .filter(|_| self.wants_thread_info(output)),
+                        source_loc: Some((file, line)),
+                        crate_tag: None,
+                        module: None,
+                        line_style: None,
+                        fields,
+                        repeat_meta: false,
+                    };
+                    let mut frame = Vec::new();
+                    msgpack::encode_record(&mut frame, &self.format, record, &segments);
+                    output.write_bytes(&frame);
+                    if output.flush_each_line {
+                        output.flush();
+                    }
+                    continue;
+                }
+            };
+            let mut segments = Segments {
+                sequence,
+                timestamp: ts.as_deref().filter(|_| self.wants_timestamps(output)),
+                thread_info: thread_info_str
+                    .as_deref()
+                    .filter(|_| self.wants_thread_info(output)),
+                source_loc,
+                crate_tag: module_path
+                    .split("::")
+                    .next()
+                    .filter(|name| self.format.crate_tag && !name.is_empty()),
+                module: Some(module_path)
+                    .filter(|path| self.format.split_target && !path.is_empty()),
+                line_style: None,
+                fields,
+                repeat_meta: false,
+            };
+            if let (Some(rule), true) = (&self.color_rule, use_color) {
+                segments.line_style = *line_style.get_or_insert_with(|| {
+                    rule(&LogRecord {
+                        level,
+                        message: message.to_string(),
+                        module_path: module_path.to_string(),
+                        file: file.to_string(),
+                        line,
+                    })
+                });
+            }
+            if self.compact {
+                segments.repeat_meta = output.repeats_meta(&segments);
+            }
+            with_line_buffer(|buf| {
+                buf.reserve(estimated_len(&self.format, message, &segments));
+                format_line_into(buf, &self.format, level, message, use_color, &segments);
+                output.write_line(buf);
+            });
+            if output.flush_each_line {
+                output.flush();
+            }
+        }
+    }
+
+    /// Whether `output` shows timestamps, falling back to the logger-wide setting.
+    fn wants_timestamps(&self, output: &LogOutput) -> bool {
+        output.timestamps.unwrap_or(self.timestamps)
+    }
+
+    /// Whether `output` shows thread info, falling back to the logger-wide setting.
+    fn wants_thread_info(&self, output: &LogOutput) -> bool {
+        output.thread_info.unwrap_or(self.thread_info)
+    }
+}
+
+/// The system hostname, the default for [`LoggerBuilder::hostname`].
+fn system_hostname() -> Option<String> {
+    #[cfg(feature = "hostname")]
+    {
+        gethostname::gethostname().into_string().ok()
+    }
+    #[cfg(not(feature = "hostname"))]
+    {
+        None
+    }
+}
+
+/// Builder for configuring and initializing the global Logger.
+/// Builder for configuring and initializing the global Logger.
+pub struct LoggerBuilder {
+    level: LogLevel,
+    level_match: LevelMatch,
+    timestamps: bool,
+    clock: Option<Clock>,
+    utc_offset: Option<i32>,
+    timestamp_from: LogLevel,
+    source_location: bool,
+    thread_info: bool,
+    thread_id_style: ThreadIdStyle,
+    sequence_numbers: bool,
+    module_allow: Vec<String>,
+    module_deny: Vec<String>,
+    validate_filters: bool,
+    filter_case_insensitive: bool,
+    reload_on_sighup: bool,
+    compact: bool,
+    format: FormatOptions,
+    app_name: Option<String>,
+    app_version: Option<String>,
+    hostname: Option<String>,
+    allow_duplicate_outputs: bool,
+    keep_default_term: bool,
+    log_header: bool,
+    marker_level: LogLevel,
+    summary_on_shutdown: bool,
+    audit_output: Option<LogOutput>,
+    skip_empty: bool,
+    flush_on: Option<LogLevel>,
+    rate_limit: Option<(u32, u32)>,
+    throttle: Option<(std::time::Duration, ThrottleKey)>,
+    transform: Option<MessageTransform>,
+    color_rule: Option<ColorRule>,
+    ordering: Option<DispatchOrdering>,
+    metrics_reporter: Option<(std::time::Duration, StatsCallback)>,
+    outputs: Vec<LogOutput>,
+}
+
+impl LoggerBuilder {
+    /// Creates a new builder with the default level (`Info`) and timestamps disabled.
+    pub fn new() -> Self {
+        let default_level = env_level().unwrap_or(LogLevel::Info);
+        let format = FormatOptions {
+            output_format: std::env::var("NANOLOG_FORMAT")
+                .ok()
+                .and_then(|s| OutputFormat::from_str(&s).ok())
+                .unwrap_or_default(),
+            ..FormatOptions::default()
+        };
+
+        Self {
+            level: default_level,
+            level_match: LevelMatch::AtLeast,
+            timestamps: false,
+            clock: None,
+            utc_offset: None,
+            timestamp_from: LogLevel::Trace,
+            source_location: false,
+            thread_info: false,
+            thread_id_style: ThreadIdStyle::Debug,
+            sequence_numbers: false,
+            module_allow: Vec::new(),
+            module_deny: Vec::new(),
+            validate_filters: false,
+            filter_case_insensitive: false,
+            reload_on_sighup: false,
+            compact: false,
+            format,
+            app_name: None,
+            app_version: None,
+            hostname: None,
+            allow_duplicate_outputs: false,
+            keep_default_term: false,
+            log_header: false,
+            marker_level: LogLevel::Info,
+            summary_on_shutdown: false,
+            audit_output: None,
+            skip_empty: false,
+            flush_on: None,
+            rate_limit: None,
+            throttle: None,
+            transform: None,
+            color_rule: None,
+            ordering: None,
+            metrics_reporter: None,
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Preset for local development: `Debug` level, source locations, and a
+    /// single stderr output.
+    ///
+    /// `NANOLOGGER_LEVEL` still takes precedence over the `Debug` default. Any
+    /// builder method can be chained afterwards to adjust the preset.
+    pub fn dev_default() -> Self {
+        let level = env_level().unwrap_or(LogLevel::Debug);
+        Self::new()
+            .level(level)
+            .source_location(true)
+            .add_output(LogOutput::term(LogLevel::Trace))
+    }
+
+    /// Preset for long-running services: `Info` level with timestamps, every
+    /// record appended to the file at `path`, and `Warn`/`Error` mirrored to
+    /// stderr so an operator sees problems immediately.
+    ///
+    /// `NANOLOGGER_LEVEL` overrides the `Info` default. Each output formats its
+    /// own line, so the stderr copy is colored while the file stays plain.
+    pub fn service_default(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(Self::new()
+            .timestamps(true)
+            .add_output(LogOutput::file(LogLevel::Trace, path)?)
+            .add_output(LogOutput::term(LogLevel::Warn)))
+    }
+
+    /// Starts from a [`Preset`] bundle of settings. Any builder method can be
+    /// chained afterwards to adjust it.
+    ///
+    /// ```rust,no_run
+    /// use nanologger::{LoggerBuilder, Preset};
+    ///
+    /// LoggerBuilder::preset(Preset::Production)
+    ///     .app_name("billing")
+    ///     .init()
+    ///     .unwrap();
+    /// ```
+    pub fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Development => Self::new()
+                .level(env_level().unwrap_or(LogLevel::Trace))
+                .timestamps(true)
+                .source_location(true)
+                .add_output(LogOutput::term(LogLevel::Trace)),
+            Preset::Production => Self::new()
+                .output_format(OutputFormat::Json)
+                .timestamps(true)
+                .timezone_offset(0)
+                .add_output(LogOutput::term(LogLevel::Trace)),
+        }
+    }
+
+    /// Sets the minimum log level.
+    pub fn level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Returns the currently configured log level.
+    pub fn get_level(&self) -> LogLevel {
+        self.level
+    }
+
+    /// Sets how message levels are compared against the global level and each
+    /// output's level. Defaults to [`LevelMatch::AtLeast`].
+    pub fn level_match(mut self, level_match: LevelMatch) -> Self {
+        self.level_match = level_match;
+        self
+    }
+
+    /// Enables or disables timestamp prefixes (`HH:MM:SS`) on log messages.
+    pub fn timestamps(mut self, enabled: bool) -> Self {
+        self.timestamps = enabled;
+        self
+    }
+
+    /// Only timestamps records at `level` or more severe, e.g. `Warn` for
+    /// timestamped warnings and errors but bare info and debug lines. Has no
+    /// effect unless timestamps are on. Timestamps passed to [`log_at!`] are
+    /// always shown. Defaults to `Trace` (every record).
+    pub fn timestamp_from(mut self, level: LogLevel) -> Self {
+        self.timestamp_from = level;
+        self
+    }
+
+    /// Shows timestamps in UTC shifted by a fixed `minutes` offset (e.g. `120`
+    /// for UTC+2, `0` for UTC) instead of the host's local time, so hosts
+    /// with different or misconfigured time zones agree. A
+    /// [`clock`](Self::clock) takes precedence.
+    pub fn timezone_offset(mut self, minutes: i32) -> Self {
+        self.utc_offset = Some(minutes);
+        self
+    }
+
+    /// Replaces the real clock used for timestamps. The closure's return value
+    /// is used verbatim as the timestamp text.
+    ///
+    /// Mainly useful in tests, e.g. `.clock(|| "00:00:00.000".to_string())`.
+    pub fn clock(mut self, clock: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Enables or disables source location (`[file:line]`) in log output.
+    pub fn source_location(mut self, enabled: bool) -> Self {
+        self.source_location = enabled;
+        self
+    }
+
+    /// Shows the crate name (the `module_path` up to the first `::`) as a
+    /// colored `[name]` tag after the level tag, so combined logs can be
+    /// grepped by crate. Plain text only; records without a module path, e.g.
+    /// from [`log_fields`], get no tag. Off by default.
+    pub fn crate_tag(mut self, enabled: bool) -> Self {
+        self.format.crate_tag = enabled;
+        self
+    }
+
+    /// Sets the delimiters around the plain-text source location and whether
+    /// the file is shown as a basename. Structured formats keep separate
+    /// `file` and `line` keys.
+    pub fn source_location_format(mut self, format: SourceLocationFormat) -> Self {
+        self.format.source_format = format;
+        self
+    }
+
+    /// Enables or disables thread info (thread name or ID) in log output.
+    pub fn thread_info(mut self, enabled: bool) -> Self {
+        self.thread_info = enabled;
+        self
+    }
+
+    /// Sets how unnamed threads are shown in thread info:
+    /// [`ThreadIdStyle::Debug`] (the default) or `Numeric`.
+    pub fn thread_id_style(mut self, style: ThreadIdStyle) -> Self {
+        self.thread_id_style = style;
+        self
+    }
+
+    /// Enables or disables a `#000123`-style sequence number at the start of
+    /// each line. The counter starts at 1 and increments once per message that
+    /// passes the global level gate and module filter, so it orders records
+    /// even when timestamps collide.
+    pub fn sequence_numbers(mut self, enabled: bool) -> Self {
+        self.sequence_numbers = enabled;
+        self
+    }
+
+    /// Sets the module allow list. Only messages from modules whose paths start
+    /// with an entry in this list will be emitted.
+    pub fn module_allow(mut self, modules: Vec<String>) -> Self {
+        self.module_allow = modules;
+        self
+    }
+
+    /// Sets the module deny list. Messages from modules whose paths start with
+    /// an entry in this list will be discarded.
+    pub fn module_deny(mut self, modules: Vec<String>) -> Self {
+        self.module_deny = modules;
+        self
+    }
+
+    /// Makes [`init`](Self::init) log a one-time warning for each module allow
+    /// or deny entry that is malformed: empty entries (which match every
+    /// module), and entries that can never match a module path because of
+    /// surrounding whitespace, empty `::` segments, or characters other than
+    /// letters, digits, `_`, `-`, `.` and `::`. `-` and `.` are allowed
+    /// because `log` facade and custom targets use them, e.g. `my-crate` or
+    /// `hyper.client`. Typos of real module names can't be detected.
+    /// Off by default.
+    pub fn validate_filters(mut self, enabled: bool) -> Self {
+        self.validate_filters = enabled;
+        self
+    }
+
+    /// Matches module allow and deny entries regardless of case, for targets
+    /// with inconsistent casing. Also applies to `NANOLOG_MODULES` reloads.
+    /// Off by default.
+    pub fn filter_case_insensitive(mut self, enabled: bool) -> Self {
+        self.filter_case_insensitive = enabled;
+        self
+    }
+
+    /// Re-reads module filters from `NANOLOG_MODULES` when the process gets
+    /// SIGHUP (Unix only). The variable holds comma-separated prefixes, with a
+    /// leading `-` for deny entries (`myapp::db,-myapp::db::pool`); it is also
+    /// applied at init. When unset, the [`module_allow`](Self::module_allow) /
+    /// [`module_deny`](Self::module_deny) lists are used. The reload happens on
+    /// the next log call after the signal and swaps both lists at once.
+    #[cfg(unix)]
+    pub fn reload_on_sighup(mut self, enabled: bool) -> Self {
+        self.reload_on_sighup = enabled;
+        self
+    }
+
+    /// Sets how records are rendered: [`OutputFormat::Plain`] (the default),
+    /// `Json`, or `Logfmt`. Overrides the `NANOLOG_FORMAT` environment variable.
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.format.output_format = format;
+        self
+    }
+
+    /// Blanks out the timestamp and thread info with spaces when both match
+    /// the previous line, comparing timestamps to the second, so bursts of
+    /// records stay readable. Plain text only. Off by default.
+    ///
+    /// Each output compares against its own previous line. Compact mode always
+    /// uses [`DispatchOrdering::Strict`], overriding [`ordering`](Self::ordering).
+    ///
+    /// ```text
+    /// 14:30:05.042 (main) [INFO]  connecting
+    ///                     [INFO]  connected
+    /// 14:30:06.001 (main) [INFO]  ready
+    /// ```
+    pub fn compact(mut self, enabled: bool) -> Self {
+        self.compact = enabled;
+        self
+    }
+
+    /// Sets whether terminal outputs are colored: [`ColorChoice::Auto`] (the
+    /// default) detects a TTY, `Always` forces colors, e.g. under a CI runner
+    /// that renders ANSI, and `Never` turns them off. Applies to the `term`
+    /// output `init` adds when none are configured, too.
+    pub fn color(mut self, choice: ColorChoice) -> Self {
+        self.format.color = choice;
+        self
+    }
+
+    /// Pads everything before the message in plain-text output to the widest
+    /// such prefix seen so far, so message text starts at a consistent column
+    /// even when source locations or thread names vary in width. The column
+    /// only grows, and is shared by all outputs. Off by default.
+    pub fn align_messages(mut self, enabled: bool) -> Self {
+        self.format.align = if enabled {
+            MessageAlign::Auto(Default::default())
+        } else {
+            MessageAlign::Off
+        };
+        self
+    }
+
+    /// Packs the timestamp and level tag of plain-text lines into one
+    /// fixed-width field, so what follows starts at the same offset on every
+    /// line. The width is set at [`init`](Self::init) from the widest level
+    /// tag and the configured clock's timestamp width. Thread info moves after
+    /// the field. Combine with [`align_messages`](Self::align_messages) to
+    /// also absorb the segments after it. Off by default.
+    ///
+    /// A wider timestamp, e.g. from [`log_at!`] or a custom clock whose output
+    /// varies in width, widens the field from then on.
+    ///
+    /// ```text
+    /// 09:15:04.120 [INFO]  (main) ready
+    /// 09:15:04.121 [ERROR] (main) failed
+    /// ```
+    pub fn fixed_ts_level_field(mut self, enabled: bool) -> Self {
+        self.format.ts_level_field = enabled.then(Default::default);
+        self
+    }
+
+    /// Starts plain-text messages at a fixed `column` (counted in characters,
+    /// ignoring colors) by padding the prefix before them. Prefixes wider than
+    /// `column` push the message past it rather than being truncated. `0`
+    /// turns alignment off.
+    pub fn message_column(mut self, column: usize) -> Self {
+        self.format.align = MessageAlign::Column(column);
+        self
+    }
+
+    /// Adds `crate` and `module` keys to JSON and logfmt output, taken from
+    /// the module path: `myapp::db::pool` gives `crate=myapp
+    /// module=myapp::db::pool`. For `log` facade records the `target` is
+    /// used instead, so custom targets become queryable by subsystem. Records
+    /// without a module path get neither key. Off by default.
+    pub fn split_target(mut self, enabled: bool) -> Self {
+        self.format.split_target = enabled;
+        self
+    }
+
+    /// Shows or hides the `[LEVEL]` tag in plain-text output, e.g. when
+    /// [`MessageColorPolicy::PerLevel`] colors already convey severity. The
+    /// segments around it close up, with no extra spaces. Structured formats
+    /// keep their `level` key. On by default.
+    pub fn show_level_tag(mut self, enabled: bool) -> Self {
+        self.format.show_level_tag = enabled;
+        self
+    }
+
+    /// Sets how plain-text output shows the level: [`LevelStyle::Bracketed`]
+    /// tags (the default) or `SyslogNumeric` `<N>` prefixes.
+    pub fn level_style(mut self, style: LevelStyle) -> Self {
+        self.format.level_style = style;
+        self
+    }
+
+    /// Sets how plain-text output handles line breaks inside a message:
+    /// [`MultilinePolicy::Raw`] (the default), `Escape`, or `Split`.
+    pub fn multiline(mut self, policy: MultilinePolicy) -> Self {
+        self.format.multiline = policy;
+        self
+    }
+
+    /// Sets the line terminator appended to every record. Defaults to
+    /// [`LineEnding::Lf`].
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.format.line_ending = line_ending;
+        self
+    }
+
+    /// Sets how message text is colored on color-capable outputs. Defaults to
+    /// [`MessageColorPolicy::None`].
+    pub fn message_color(mut self, policy: MessageColorPolicy) -> Self {
+        self.format.message_color = policy;
+        self
+    }
+
+    /// Overrides the level tag colors. Levels the theme leaves unset keep
+    /// their default color. RGB and 256-color values fall back to the nearest
+    /// color the terminal supports.
+    pub fn level_colors(mut self, theme: ColorTheme) -> Self {
+        self.format.level_colors = theme;
+        self
+    }
+
+    /// Enables or disables the line ending after each record. Defaults to
+    /// `true`; disable it when the destination adds its own framing.
+    pub fn trailing_newline(mut self, enabled: bool) -> Self {
+        self.format.trailing_newline = enabled;
+        self
+    }
+
+    /// Sets the application name shown after the level tag on every line.
+    pub fn app_name(mut self, name: impl Into<String>) -> Self {
+        self.app_name = Some(name.into());
+        self
+    }
+
+    /// Sets the hostname added to every record: a `host` key in structured
+    /// formats and a segment after the app name in plain text. With the
+    /// `hostname` feature it defaults to the system hostname, emitted only as
+    /// the structured `host` key; otherwise it is omitted. An empty name
+    /// omits it, including the detected one.
+    pub fn hostname(mut self, name: impl Into<String>) -> Self {
+        self.hostname = Some(name.into());
+        self
+    }
+
+    /// Sets a build identifier added to every record, e.g. a short git SHA
+    /// exposed by a build script: a `build` key in structured formats and a
+    /// segment after the hostname in plain text. An empty id omits it.
+    ///
+    /// ```rust,no_run
+    /// nanologger::LoggerBuilder::new()
+    ///     .build_id(option_env!("GIT_SHA").unwrap_or("dev"))
+    ///     .init()
+    ///     .unwrap();
+    /// ```
+    pub fn build_id(mut self, id: impl Into<String>) -> Self {
+        self.format.build = Some(id.into()).filter(|id| !id.is_empty());
+        self
+    }
+
+    /// Sets the application version, shown as `name@version` after the level
+    /// tag. Ignored unless [`app_name`](Self::app_name) is also set.
+    pub fn app_version(mut self, version: impl Into<String>) -> Self {
+        self.app_version = Some(version.into());
+        self
+    }
+
+    /// Adds a log output destination. Multiple outputs can be added; each
+    /// applies its own level filter independently.
+    ///
+    /// Without any outputs, [`init`](Self::init) logs to stderr through a
+    /// [`LogOutput::term`] at the builder's level. Adding an output replaces
+    /// that default unless [`keep_default_term`](Self::keep_default_term) is set.
+    pub fn add_output(mut self, output: LogOutput) -> Self {
+        self.outputs.push(output);
+        self
+    }
+
+    /// Keeps the default stderr [`LogOutput::term`] output (at the builder's
+    /// level) when other outputs are added, e.g. so enabling a log file still
+    /// logs to the terminal. Has no effect if a `term` output writing to
+    /// stderr was added explicitly. Off by default.
+    ///
+    /// The kept output goes after the added ones, so their indexes in
+    /// [`output_bytes`] and [`outputs`] don't change.
+    pub fn keep_default_term(mut self, keep: bool) -> Self {
+        self.keep_default_term = keep;
+        self
+    }
+
+    /// Allows several outputs to write to the same destination, such as two
+    /// [`LogOutput::term`] outputs. Off by default, in which case [`init`](Self::init)
+    /// returns [`InitError::DuplicateOutput`].
+    pub fn allow_duplicate_outputs(mut self, allow: bool) -> Self {
+        self.allow_duplicate_outputs = allow;
+        self
+    }
+
+    /// Emits a self-describing Info record from [`init`](Self::init), e.g.
+    /// `nanologger 0.2.0 level=trace timestamps=true ... format=plain`, so a log
+    /// file documents its own settings. The header skips the global level gate
+    /// and module filter but respects each output's level filter. It is not
+    /// counted in [`stats`] and bypasses throttling, rate limiting, and
+    /// [`message_transform`](Self::message_transform).
+    pub fn log_header(mut self, enabled: bool) -> Self {
+        self.log_header = enabled;
+        self
+    }
+
+    /// Calls `callback` with the current [`LogStats`] every `interval` from a
+    /// background thread, for pushing counts to a metrics exporter. The thread
+    /// is stopped by [`shutdown`], which waits at most one second for a
+    /// running callback to return.
+    pub fn metrics_reporter(
+        mut self,
+        interval: std::time::Duration,
+        callback: impl Fn(&LogStats) + Send + 'static,
+    ) -> Self {
+        self.metrics_reporter = Some((interval, Box::new(callback)));
+        self
+    }
+
+    /// Chooses how concurrent records are ordered across outputs. Defaults to
+    /// [`DispatchOrdering::Strict`] when a [`LogOutput::term`] output is
+    /// configured and [`DispatchOrdering::PerOutput`] otherwise.
+    pub fn ordering(mut self, ordering: DispatchOrdering) -> Self {
+        self.ordering = Some(ordering);
+        self
+    }
+
+    /// Drops records whose message is empty or whitespace-only, e.g. a stray
+    /// `info!("")` in a dependency. Off by default.
+    pub fn skip_empty(mut self, enabled: bool) -> Self {
+        self.skip_empty = enabled;
+        self
+    }
+
+    /// Flushes every output right after a record at `level` or more severe is
+    /// written, so e.g. the last error before a crash reaches disk. Off by
+    /// default; lower levels never pay for the flush.
+    pub fn flush_on(mut self, level: LogLevel) -> Self {
+        self.flush_on = Some(level);
+        self
+    }
+
+    /// Caps the number of records written to about `rate` per second across
+    /// all threads, allowing bursts of up to `burst` records, to protect
+    /// downstream collectors during a log storm. Excess records are dropped,
+    /// and at most once a second the next record that gets through is
+    /// preceded by a `dropped N lines due to rate limit` warning. Dropped
+    /// records are not counted in [`stats`]. Off by default.
+    ///
+    /// A `rate` or `burst` of 0 is treated as 1.
+    pub fn global_rate_limit(mut self, rate: u32, burst: u32) -> Self {
+        self.rate_limit = Some((rate, burst));
+        self
+    }
+
+    /// Writes a recurring message at most once per `window`, even when other
+    /// records come in between. Records are compared by `key`. When a
+    /// throttled message is next written after its window, it is preceded by
+    /// a `suppressed N repeats of "..."` note at the same level. Suppressed
+    /// records are not counted in [`stats`]. Off by default.
+    ///
+    /// Up to 1024 distinct messages are tracked; the least recently seen is
+    /// forgotten first, along with its suppressed count.
+    pub fn throttle(mut self, window: std::time::Duration, by: ThrottleKey) -> Self {
+        self.throttle = Some((window, by));
+        self
+    }
+
+    /// Rewrites every message before it is formatted, e.g. to replace internal
+    /// hostnames or prepend a trace id from a thread-local. Runs on the
+    /// logging thread after all filters (level, module, throttle, rate limit),
+    /// so dropped records cost nothing. Return `Cow::Borrowed` to keep a
+    /// message unchanged without allocating.
+    ///
+    /// ```rust,no_run
+    /// use std::borrow::Cow;
+    ///
+    /// nanologger::LoggerBuilder::new()
+    ///     .message_transform(|msg| {
+    ///         if msg.contains("db.internal") {
+    ///             Cow::Owned(msg.replace("db.internal", "db.example.com"))
+    ///         } else {
+    ///             Cow::Borrowed(msg)
+    ///         }
+    ///     })
+    ///     .init()
+    ///     .unwrap();
+    /// ```
+    pub fn message_transform(
+        mut self,
+        f: impl for<'a> Fn(&'a str) -> std::borrow::Cow<'a, str> + Send + Sync + 'static,
+    ) -> Self {
+        self.transform = Some(Box::new(f));
+        self
+    }
+
+    /// Highlights whole lines on colored outputs, e.g. every message
+    /// mentioning an SLA breach in bright red regardless of level. `f` runs
+    /// once per record that reaches a colored output; a [`LineStyle`] it
+    /// returns replaces the level colors for the whole line, and `None` keeps
+    /// them. Plain-text writers and structured formats are unaffected.
+    ///
+    /// ```rust,no_run
+    /// use nanologger::{Color, LineStyle};
+    ///
+    /// nanologger::LoggerBuilder::new()
+    ///     .color_rule(|record| {
+    ///         record
+    ///             .message
+    ///             .contains("SLA breach")
+    ///             .then(|| LineStyle::new(Color::BrightRed).bold())
+    ///     })
+    ///     .init()
+    ///     .unwrap();
+    /// ```
+    pub fn color_rule(
+        mut self,
+        f: impl Fn(&LogRecord) -> Option<LineStyle> + Send + Sync + 'static,
+    ) -> Self {
+        self.color_rule = Some(Box::new(f));
+        self
+    }
+
+    /// Sets the output that [`audit!`] records go to. Without one, `audit!` is
+    /// a no-op. The audit output is separate from [`add_output`](Self::add_output)
+    /// outputs and ignores its own level filter.
+    pub fn audit_output(mut self, output: LogOutput) -> Self {
+        self.audit_output = Some(output);
+        self
+    }
+
+    /// Sets the level that [`marker`] lines are logged at. Defaults to `Info`.
+    pub fn marker_level(mut self, level: LogLevel) -> Self {
+        self.marker_level = level;
+        self
+    }
+
+    /// Makes [`shutdown`] (and so a dropped [`FlushGuard`]) write the
+    /// [`log_summary`] line before flushing. Off by default.
+    pub fn summary_on_shutdown(mut self, enabled: bool) -> Self {
+        self.summary_on_shutdown = enabled;
+        self
+    }
+
+    /// Initializes the global logger. Returns [`InitError::AlreadyInitialized`]
+    /// if already initialized, or [`InitError::DuplicateOutput`] if more than
+    /// one output writes to stderr.
+    ///
+    /// When the `log` feature is enabled, this also registers the logger with the
+    /// `log` facade via `log::set_logger` and `log::set_max_level`. If another
+    /// logger already claimed the facade, [`InitError::LogFacadeClaimed`] is
+    /// returned; nanologger's own macros are still initialized in that case.
+    pub fn init(mut self) -> Result<(), InitError> {
+        if !self.allow_duplicate_outputs {
+            let terms = self
+                .outputs
+                .iter()
+                .filter(|o| matches!(o.sink, OutputSink::Term) && o.term_writer.is_none())
+                .count();
+            if terms > 1 {
+                return Err(InitError::DuplicateOutput("stderr"));
+            }
+        }
+        self.format.color_depth = ColorDepth::detect();
+        if let Some(field) = &mut self.format.ts_level_field {
+            let sample = match (&self.clock, self.utc_offset) {
+                (Some(clock), _) => clock(),
+                (None, Some(offset)) => format_offset_timestamp(offset),
+                (None, None) => format_current_timestamp(),
+            };
+            field.timestamp = sample.chars().count();
+            field.tag = match self.format.level_style {
+                LevelStyle::Bracketed if self.format.show_level_tag => LogLevel::ALL
+                    .iter()
+                    .map(|level| level.tag_str().len() + 1)
+                    .max()
+                    .unwrap_or(0),
+                _ => 0,
+            };
+        }
+        self.format.app = match (self.app_name, self.app_version) {
+            (Some(name), Some(version)) => Some(format!("{name}@{version}")),
+            (Some(name), None) => Some(name),
+            (None, _) => None,
+        };
+        self.format.host_detected = self.hostname.is_none();
+        self.format.host = self
+            .hostname
+            .or_else(system_hostname)
+            .filter(|host| !host.is_empty());
+        let has_stderr_term = self
+            .outputs
+            .iter()
+            .any(|o| matches!(o.sink, OutputSink::Term) && o.term_writer.is_none());
+        let mut outputs = self.outputs;
+        if outputs.is_empty() || (self.keep_default_term && !has_stderr_term) {
+            outputs.push(LogOutput::term(self.level));
+        }
+        // Compact mode compares each line with the one before, which needs
+        // records to reach an output one at a time.
+        let ordering = self.ordering.filter(|_| !self.compact).unwrap_or_else(|| {
+            if self.compact || outputs.iter().any(|o| matches!(o.sink, OutputSink::Term)) {
+                DispatchOrdering::Strict
+            } else {
+                DispatchOrdering::PerOutput
+            }
+        });
+        let filter_warnings: Vec<String> = if self.validate_filters {
+            let allow = self.module_allow.iter().map(|entry| ("allow", entry));
+            let deny = self.module_deny.iter().map(|entry| ("deny", entry));
+            allow
+                .chain(deny)
+                .filter_map(|(list, entry)| {
+                    let problem = module_filter_entry_problem(entry)?;
+                    Some(format!(
+                        "suspicious module {list} entry {entry:?}: {problem}"
+                    ))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let base_modules = if self.filter_case_insensitive {
+            ModuleFilter::new_case_insensitive(self.module_allow, self.module_deny)
+        } else {
+            ModuleFilter::new(self.module_allow, self.module_deny)
+        };
+        let logger = Logger {
+            level: AtomicU8::new(self.level.as_u8()),
+            level_match: self.level_match,
+            timestamps: self.timestamps,
+            clock: self.clock,
+            utc_offset: self.utc_offset,
+            timestamp_from: self.timestamp_from,
+            source_location: self.source_location,
+            thread_info: self.thread_info,
+            thread_id_style: self.thread_id_style,
+            sequence_numbers: self.sequence_numbers,
+            sequence: AtomicU64::new(0),
+            modules: std::sync::RwLock::new(if self.reload_on_sighup {
+                ModuleFilter::from_env_or(&base_modules)
+            } else {
+                base_modules.clone()
+            }),
+            base_modules,
+            reload_on_sighup: self.reload_on_sighup,
+            format: self.format,
+            marker_level: self.marker_level,
+            summary_on_shutdown: self.summary_on_shutdown,
+            audit_output: self.audit_output,
+            skip_empty: self.skip_empty,
+            flush_on: self.flush_on,
+            rate_limit: self
+                .rate_limit
+                .map(|(rate, burst)| RateLimiter::new(rate, burst)),
+            throttle: self.throttle.map(|(window, key)| Throttle {
+                window,
+                key,
+                entries: Default::default(),
+            }),
+            transform: self.transform,
+            color_rule: self.color_rule,
+            dispatch_lock: match ordering {
+                DispatchOrdering::Strict => Some(std::sync::Mutex::new(())),
+                DispatchOrdering::PerOutput => None,
+            },
+            compact: self.compact,
+            counts: Default::default(),
+            reporter: std::sync::Mutex::new(None),
+            outputs,
+        };
+        LOGGER
+            .set(logger)
+            .map_err(|_| InitError::AlreadyInitialized)?;
+
+        if self.log_header {
+            // Written straight to the outputs: not counted, throttled, rate
+            // limited, or transformed like user records.
+            let logger = LOGGER.get().expect("just set");
+            logger.dispatch_to(
+                &logger.outputs,
+                true,
+                &Record {
+                    level: LogLevel::Info,
+                    message: &logger.header(),
+                    module_path: module_path!(),
+                    file: file!(),
+                    line: line!(),
+                    fields: &[],
+                    timestamp: None,
+                },
+            );
+        }
+        for warning in &filter_warnings {
+            let logger = LOGGER.get().expect("just set");
+            logger.dispatch(&Record {
+                level: LogLevel::Warn,
+                message: warning,
+                module_path: module_path!(),
+                file: file!(),
+                line: line!(),
+                fields: &[],
+                timestamp: None,
+            });
+        }
+        replay_early_records(LOGGER.get().expect("just set"));
+        #[cfg(unix)]
+        if self.reload_on_sighup {
+            sighup::install();
+        }
+        if let Some((interval, callback)) = self.metrics_reporter {
+            let logger = LOGGER.get().expect("just set");
+            *logger.reporter.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some(MetricsReporter::spawn(interval, callback));
+        }
+
+        #[cfg(feature = "log")]
+        {
+            // SAFETY: LOGGER is a static OnceLock that was just set above and will
+            // never be dropped, so the reference is valid for 'static.
+            let logger_ref: &'static Logger =
+                unsafe { &*(LOGGER.get().expect("just set") as *const Logger) };
+            log::set_logger(logger_ref).map_err(|_| InitError::LogFacadeClaimed)?;
+            log::set_max_level(logger_ref.facade_max_level());
+        }
+
+        Ok(())
+    }
+
+    /// Initializes the global logger like [`init`](Self::init) and returns a
+    /// [`FlushGuard`] that flushes all outputs when dropped.
+    ///
+    /// Hold the guard until the end of `main` so buffered outputs are flushed
+    /// on exit.
+    pub fn init_with_guard(self) -> Result<FlushGuard, InitError> {
+        self.init()?;
+        Ok(FlushGuard { _private: () })
+    }
+}
+
+/// Reads the level from the `NANOLOGGER_LEVEL` environment variable, if set and valid.
+fn env_level() -> Option<LogLevel> {
+    std::env::var("NANOLOGGER_LEVEL")
+        .ok()
+        .and_then(|s| LogLevel::from_str(&s).ok())
+}
+
+impl Default for LoggerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Flushes all outputs of the global logger when dropped.
+///
+/// Returned by [`LoggerBuilder::init_with_guard`]. The global logger itself is
+/// never dropped, so this guard is the way to get a deterministic flush on exit.
+#[must_use = "the guard flushes the logger when dropped; bind it to a variable"]
+pub struct FlushGuard {
+    _private: (),
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        shutdown();
+    }
+}
+
+/// Error returned when the logger cannot be initialized.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InitError {
+    /// The global logger was already initialized.
+    AlreadyInitialized,
+    /// Another logger is already registered with the `log` facade.
+    LogFacadeClaimed,
+    /// An output could not be opened, e.g. by [`LogOutput::file`].
+    OutputIoError(std::io::Error),
+    /// More than one output writes to the same destination (e.g. two
+    /// [`LogOutput::term`] outputs), which would print every line twice.
+    /// Allow it with [`LoggerBuilder::allow_duplicate_outputs`].
+    DuplicateOutput(&'static str),
+}
+
+impl std::fmt::Display for InitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InitError::AlreadyInitialized => write!(f, "nanologger: logger already initialized"),
+            InitError::LogFacadeClaimed => write!(
+                f,
+                "nanologger: another logger is already registered with the log facade"
+            ),
+            InitError::OutputIoError(e) => write!(f, "nanologger: failed to open output: {e}"),
+            InitError::DuplicateOutput(dest) => write!(
+                f,
+                "nanologger: more than one output writes to {dest}; \
+                 use allow_duplicate_outputs(true) if this is intended"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InitError::OutputIoError(e) => Some(e),
+            InitError::AlreadyInitialized
+            | InitError::LogFacadeClaimed
+            | InitError::DuplicateOutput(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for InitError {
+    fn from(e: std::io::Error) -> Self {
+        InitError::OutputIoError(e)
+    }
+}
+
+/// Convenience function: initialize the global logger with default settings (level = Info).
+pub fn init() -> Result<(), InitError> {
+    LoggerBuilder::new().init()
+}
+
+/// Initializes the global logger with the builder returned by `configure`,
+/// unless it is already initialized.
+///
+/// Meant for modular apps where several components may each try to set up
+/// logging: the closure runs at most once across all `ensure_init` calls, and
+/// not at all if the logger is already initialized. Returns `Ok(true)` if this
+/// call initialized the logger and `Ok(false)` if it was already initialized.
+///
+/// ```rust,no_run
+/// use nanologger::{LogLevel, LoggerBuilder};
+///
+/// // In each component:
+/// nanologger::ensure_init(|| LoggerBuilder::new().level(LogLevel::Debug)).unwrap();
+///
+/// // Later calls are no-ops and never build their config.
+/// assert!(!nanologger::ensure_init(LoggerBuilder::new).unwrap());
+/// ```
+pub fn ensure_init(configure: impl FnOnce() -> LoggerBuilder) -> Result<bool, InitError> {
+    static ENSURE_INIT: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = ENSURE_INIT.lock().unwrap_or_else(|e| e.into_inner());
+    if LOGGER.get().is_some() {
+        return Ok(false);
+    }
+    match configure().init() {
+        Ok(()) => Ok(true),
+        Err(InitError::AlreadyInitialized) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Changes the global log level at runtime.
+///
+/// The new level is published with `Release` ordering and read with `Acquire`
+/// on every log call, so the change is visible to the calling thread
+/// immediately and to other threads on their next log call after they observe
+/// it. Memory written before `set_level` (e.g. reloaded config) happens-before
+/// any log call that sees the new level. A log call racing with `set_level` on
+/// another thread may still use the old level.
+///
+/// Has no effect if the logger has not been initialized.
+pub fn set_level(level: LogLevel) {
+    if let Some(logger) = LOGGER.get() {
+        logger.level.store(level.as_u8(), Ordering::Release);
+
+        #[cfg(feature = "log")]
+        log::set_max_level(logger.facade_max_level());
+    }
+}
+
+thread_local! {
+    /// Per-thread level override set by [`set_thread_level`].
+    static THREAD_LEVEL: std::cell::Cell<Option<LogLevel>> = const { std::cell::Cell::new(None) };
+}
+
+/// Set once any thread installs a level override, so the `log` facade stops
+/// pre-filtering by the global level.
+#[cfg(feature = "log")]
+static THREAD_LEVEL_USED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Overrides the global log level for the calling thread only.
+///
+/// `Some(level)` replaces the global level in the level gate for messages
+/// logged from this thread, whether more or less verbose. `None` removes the
+/// override and falls back to the global level. Per-output filters still apply.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// std::thread::spawn(|| {
+///     nanologger::set_thread_level(Some(LogLevel::Trace));
+///     nanologger::trace!("only this worker logs at Trace");
+/// });
+/// ```
+pub fn set_thread_level(level: Option<LogLevel>) {
+    THREAD_LEVEL.with(|cell| cell.set(level));
+
+    #[cfg(feature = "log")]
+    if level.is_some() && !THREAD_LEVEL_USED.swap(true, Ordering::Relaxed) {
+        if let Some(logger) = LOGGER.get() {
+            log::set_max_level(logger.facade_max_level());
+        }
+    }
+}
+
+/// Returns the calling thread's level override, if any.
+pub fn thread_level() -> Option<LogLevel> {
+    THREAD_LEVEL.with(|cell| cell.get())
+}
+
+/// Returns a [`ThreadBuilder`] for spawning a thread with its own level
+/// override already set.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// let worker = nanologger::thread_builder()
+///     .name("worker-1")
+///     .level(LogLevel::Trace)
+///     .spawn(|| nanologger::trace!("only this worker logs at Trace"))
+///     .unwrap();
+/// worker.join().unwrap();
+/// ```
+pub fn thread_builder() -> ThreadBuilder {
+    ThreadBuilder {
+        inner: std::thread::Builder::new(),
+        level: None,
+    }
+}
+
+/// Wraps [`std::thread::Builder`] and calls [`set_thread_level`] as the first
+/// thing the spawned thread does. Created with [`thread_builder`].
+#[derive(Debug)]
+pub struct ThreadBuilder {
+    inner: std::thread::Builder,
+    level: Option<LogLevel>,
+}
+
+impl ThreadBuilder {
+    /// Names the thread, as [`std::thread::Builder::name`].
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.inner = self.inner.name(name.into());
+        self
+    }
+
+    /// Sets the thread's stack size in bytes, as
+    /// [`std::thread::Builder::stack_size`].
+    pub fn stack_size(mut self, size: usize) -> Self {
+        self.inner = self.inner.stack_size(size);
+        self
+    }
+
+    /// Sets the spawned thread's level override. Without it the thread uses
+    /// the global level.
+    pub fn level(mut self, level: LogLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Spawns the thread, as [`std::thread::Builder::spawn`].
+    pub fn spawn<F, T>(self, f: F) -> std::io::Result<std::thread::JoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let level = self.level;
+        self.inner.spawn(move || {
+            if level.is_some() {
+                set_thread_level(level);
+            }
+            f()
+        })
+    }
+}
+
+/// Tears the logger down at the end of the program: stops the
+/// [`metrics_reporter`](LoggerBuilder::metrics_reporter) thread, if any, and
+/// flushes every output.
+///
+/// Waits up to one second for the reporter thread to exit; if its callback is
+/// still blocked after that, the thread is detached rather than joined.
+///
+/// Outputs write synchronously on the calling thread, so logging after
+/// `shutdown` still works. Safe to call more than once, and a no-op if the
+/// logger has not been initialized.
+pub fn shutdown() {
+    if let Some(logger) = LOGGER.get() {
+        let reporter = logger
+            .reporter
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(reporter) = reporter {
+            reporter.stop();
+        }
+        if logger.summary_on_shutdown {
+            logger.log_summary();
+        }
+        logger.flush_outputs();
+    }
+}
+
+/// Flushes every output without blocking an async runtime.
+///
+/// Outputs write synchronously, so there is no queue to drain; the returned
+/// future runs the blocking flush on a short-lived helper thread and completes
+/// when it is done. Works with any executor. Completes immediately if the
+/// logger has not been initialized.
+///
+/// ```rust,no_run
+/// # async fn run() {
+/// nanologger::flush_async().await;
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub fn flush_async() -> FlushFuture {
+    FlushFuture { state: None }
+}
+
+/// Future returned by [`flush_async`].
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless awaited"]
+pub struct FlushFuture {
+    /// Whether the flush finished, and the waker to call when it does. `None`
+    /// until first polled.
+    state: Option<std::sync::Arc<std::sync::Mutex<(bool, std::task::Waker)>>>,
+}
+
+#[cfg(feature = "async")]
+impl std::future::Future for FlushFuture {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<()> {
+        let Some(logger) = LOGGER.get() else {
+            return std::task::Poll::Ready(());
+        };
+        match &self.state {
+            Some(state) => {
+                let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                if state.0 {
+                    return std::task::Poll::Ready(());
+                }
+                state.1.clone_from(cx.waker());
+            }
+            None => {
+                let state = std::sync::Arc::new(std::sync::Mutex::new((false, cx.waker().clone())));
+                let done = state.clone();
+                let spawned = std::thread::Builder::new()
+                    .name("nanologger-flush".to_string())
+                    .spawn(move || {
+                        logger.flush_outputs();
+                        let mut done = done.lock().unwrap_or_else(|e| e.into_inner());
+                        done.0 = true;
+                        done.1.wake_by_ref();
+                    });
+                if spawned.is_err() {
+                    logger.flush_outputs();
+                    return std::task::Poll::Ready(());
+                }
+                self.state = Some(state);
+            }
+        }
+        std::task::Poll::Pending
+    }
+}
+
+/// Per-level counts of records dispatched since init.
+///
+/// A record is counted once when it passes the global level gate and module
+/// filter, regardless of how many outputs write it. [`audit!`] records are
+/// not counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LogStats {
+    counts: [u64; 5],
+}
+
+impl LogStats {
+    /// Returns how many records were dispatched at `level`.
+    pub fn get(&self, level: LogLevel) -> u64 {
+        self.counts[level.as_u8() as usize]
+    }
+
+    /// Returns how many records were dispatched across all levels.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// Returns the per-level record counts, or `None` if the logger is not initialized.
+pub fn stats() -> Option<LogStats> {
+    LOGGER.get().map(Logger::stats)
+}
+
+/// Writes a one-line run summary from the [`stats`] counters, such as
+/// `nanologger: 12 errors, 48 warnings during run (1302 records)`, to every
+/// output.
+///
+/// The line is logged at `Error` if any errors were counted, `Warn` if any
+/// warnings were, and `Info` otherwise. It skips the level filters so it
+/// always closes the log, and isn't counted itself. No-op if the logger has
+/// not been initialized. See also [`LoggerBuilder::summary_on_shutdown`].
+pub fn log_summary() {
+    if let Some(logger) = LOGGER.get() {
+        logger.log_summary();
+    }
+}
+
+/// The [`log_summary`] text for `stats`, with the level to log it at.
+fn format_summary(stats: &LogStats) -> (LogLevel, String) {
+    let plural = |n: u64, one: &str, many: &str| format!("{n} {}", if n == 1 { one } else { many });
+    let errors = stats.get(LogLevel::Error);
+    let warnings = stats.get(LogLevel::Warn);
+    let level = if errors > 0 {
+        LogLevel::Error
+    } else if warnings > 0 {
+        LogLevel::Warn
+    } else {
+        LogLevel::Info
+    };
+    let message = format!(
+        "nanologger: {}, {} during run ({})",
+        plural(errors, "error", "errors"),
+        plural(warnings, "warning", "warnings"),
+        plural(stats.total(), "record", "records"),
+    );
+    (level, message)
+}
+
+/// Runs `f` and returns everything [`LogOutput::test`] outputs wrote on this
+/// thread meanwhile, instead of printing it.
+///
+/// Only records logged from the calling thread are captured, so tests running
+/// in parallel don't see each other's lines. Captures nest; the innermost one
+/// receives the lines.
+///
+/// ```
+/// # use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+/// LoggerBuilder::new()
+///     .add_output(LogOutput::test(LogLevel::Info))
+///     .init()
+///     .unwrap();
+/// let out = nanologger::capture_test_output(|| nanologger::info!("ready"));
+/// assert_eq!(out, "[INFO]  ready\n");
+/// ```
+#[cfg(feature = "test-util")]
+pub fn capture_test_output(f: impl FnOnce()) -> String {
+    test_capture::capture(f)
+}
+
+/// Runs `f` and panics, listing the offending records, if anything more
+/// severe than `level` was logged on this thread meanwhile. Returns what `f`
+/// returned otherwise.
+///
+/// With `LogLevel::Info`, any warning or error fails the assertion. Records
+/// are checked after the global level and module filters, whatever outputs
+/// are configured, so a quieter global level hides them. Only the calling
+/// thread is watched, so tests running in parallel don't interfere.
+///
+/// ```
+/// # use nanologger::{LogLevel, LoggerBuilder};
+/// # LoggerBuilder::new().init().unwrap();
+/// let total = nanologger::assert_no_logs_above(LogLevel::Info, || {
+///     nanologger::info!("summing");
+///     1 + 2
+/// });
+/// assert_eq!(total, 3);
+/// ```
+#[cfg(feature = "test-util")]
+#[track_caller]
+pub fn assert_no_logs_above<R>(level: LogLevel, f: impl FnOnce() -> R) -> R {
+    let (result, records) = test_capture::watch(f);
+    let offending: Vec<String> = records
+        .into_iter()
+        .filter(|(record_level, _)| *record_level < level)
+        .map(|(record_level, message)| format!("  {} {message}", record_level.tag_str()))
+        .collect();
+    if !offending.is_empty() {
+        panic!(
+            "expected no logs above {}, got {}:\n{}",
+            level.as_str_upper(),
+            offending.len(),
+            offending.join("\n")
+        );
+    }
+    result
+}
+
+#[cfg(feature = "test-util")]
+mod test_capture {
+    use super::LogLevel;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static CAPTURE: RefCell<Option<String>> = const { RefCell::new(None) };
+        static WATCH: RefCell<Option<Vec<(LogLevel, String)>>> = const { RefCell::new(None) };
+    }
+
+    /// Restores the enclosing watch, even if the closure panics.
+    struct RestoreWatch(Option<Vec<(LogLevel, String)>>);
+
+    impl Drop for RestoreWatch {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            WATCH.with(|w| *w.borrow_mut() = outer);
+        }
+    }
+
+    /// Runs `f`, collecting the level and message of every record it logs.
+    pub(crate) fn watch<R>(f: impl FnOnce() -> R) -> (R, Vec<(LogLevel, String)>) {
+        let restore = RestoreWatch(WATCH.with(|w| w.borrow_mut().replace(Vec::new())));
+        let result = f();
+        let records = WATCH.with(|w| w.borrow_mut().take()).unwrap_or_default();
+        drop(restore);
+        (result, records)
+    }
+
+    /// Adds a dispatched record to the active watch, if any.
+    pub(crate) fn observe(level: LogLevel, message: &str) {
+        WATCH.with(|w| {
+            if let Some(records) = w.borrow_mut().as_mut() {
+                records.push((level, message.to_string()));
+            }
+        });
+    }
+
+    /// Restores the enclosing capture, even if the closure panics.
+    struct Restore(Option<String>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            CAPTURE.with(|c| *c.borrow_mut() = outer);
+        }
+    }
+
+    pub(crate) fn capture(f: impl FnOnce()) -> String {
+        let restore = Restore(CAPTURE.with(|c| c.borrow_mut().replace(String::new())));
+        f();
+        let captured = CAPTURE.with(|c| c.borrow_mut().take()).unwrap_or_default();
+        drop(restore);
+        captured
+    }
+
+    /// Appends `line` to the active capture. Returns false when none is active.
+    pub(crate) fn push(line: &str) -> bool {
+        CAPTURE.with(|c| match c.borrow_mut().as_mut() {
+            Some(buf) => {
+                buf.push_str(line);
+                true
+            }
+            None => false,
+        })
+    }
+}
+
+/// Callback registered with [`LoggerBuilder::metrics_reporter`].
+type StatsCallback = Box<dyn Fn(&LogStats) + Send>;
+
+/// Minimum time between two rate limit drop summaries.
+const RATE_SUMMARY_INTERVAL_NS: u64 = 1_000_000_000;
+
+/// Lock-free token bucket for [`LoggerBuilder::global_rate_limit`].
+///
+/// Uses the generic cell rate algorithm: a single atomic holds the time at
+/// which the bucket will be full again, so admission is one CAS.
+struct RateLimiter {
+    start: std::time::Instant,
+    /// Nanoseconds it takes to earn one token.
+    interval_ns: u64,
+    /// How far ahead of now the full time may run before records are dropped.
+    tolerance_ns: u64,
+    /// Theoretical arrival time of the next record, in ns since `start`.
+    next_ns: AtomicU64,
+    dropped: AtomicU64,
+    /// When the last drop summary was emitted, in ns since `start`, plus one
+    /// so that 0 means never.
+    last_summary_ns: AtomicU64,
+}
+
+impl RateLimiter {
+    fn new(rate: u32, burst: u32) -> Self {
+        let interval_ns = 1_000_000_000 / u64::from(rate.max(1));
+        RateLimiter {
+            start: std::time::Instant::now(),
+            interval_ns,
+            tolerance_ns: interval_ns * u64::from(burst.max(1) - 1),
+            next_ns: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            last_summary_ns: AtomicU64::new(0),
+        }
+    }
+
+    fn now_ns(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+
+    /// Takes a token, or counts a drop and returns false if none is left.
+    fn try_acquire(&self) -> bool {
+        let now = self.now_ns();
+        let mut next = self.next_ns.load(Ordering::Relaxed);
+        loop {
+            if next.saturating_sub(self.tolerance_ns) > now {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+            let claimed = next.max(now) + self.interval_ns;
+            match self.next_ns.compare_exchange_weak(
+                next,
+                claimed,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(actual) => next = actual,
+            }
+        }
+    }
+
+    /// Returns the number of records dropped since the last summary if one
+    /// is due. Only one caller wins each summary.
+    fn take_summary(&self) -> Option<u64> {
+        if self.dropped.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        let now = self.now_ns() + 1;
+        let last = self.last_summary_ns.load(Ordering::Relaxed);
+        if last != 0 && now.saturating_sub(last) < RATE_SUMMARY_INTERVAL_NS {
+            return None;
+        }
+        self.last_summary_ns
+            .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+            .ok()?;
+        Some(self.dropped.swap(0, Ordering::Relaxed)).filter(|&n| n > 0)
+    }
+}
+
+/// How long [`shutdown`] waits for the metrics reporter thread to exit before
+/// detaching it, e.g. when its callback is blocked.
+const REPORTER_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// A timer thread that periodically reports [`LogStats`].
+struct MetricsReporter {
+    stop: std::sync::Arc<(std::sync::Mutex<bool>, std::sync::Condvar)>,
+    /// Disconnects when the thread exits, since it holds the only sender.
+    done: std::sync::mpsc::Receiver<()>,
+    handle: std::thread::JoinHandle<()>,
+}
+
+impl MetricsReporter {
+    fn spawn(interval: std::time::Duration, callback: StatsCallback) -> Self {
+        let stop = std::sync::Arc::new((std::sync::Mutex::new(false), std::sync::Condvar::new()));
+        let signal = stop.clone();
+        let (done_tx, done) = std::sync::mpsc::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("nanologger-metrics".to_string())
+            .spawn(move || {
+                let _done = done_tx;
+                let (stopped, wake) = &*signal;
+                loop {
+                    let guard = stopped.lock().unwrap_or_else(|e| e.into_inner());
+                    let (guard, timeout) = wake
+                        .wait_timeout_while(guard, interval, |stopped| !*stopped)
+                        .unwrap_or_else(|e| e.into_inner());
+                    if !timeout.timed_out() {
+                        return;
+                    }
+                    // Unlocked while the callback runs, so `stop` can still
+                    // signal a blocked callback.
+                    drop(guard);
+                    if let Some(stats) = stats() {
+                        callback(&stats);
+                    }
+                }
+            })
+            .expect("failed to spawn metrics reporter thread");
+        MetricsReporter { stop, done, handle }
+    }
+
+    /// Signals the thread to exit and joins it, or detaches it if it is still
+    /// running after [`REPORTER_STOP_TIMEOUT`].
+    fn stop(self) {
+        let (stopped, wake) = &*self.stop;
+        *stopped.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        wake.notify_all();
+        match self.done.recv_timeout(REPORTER_STOP_TIMEOUT) {
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+            _ => {
+                let _ = self.handle.join();
+            }
+        }
+    }
+}
+
+/// Writes a separator line such as `---- batch 3 done ----` to every output,
+/// then flushes them all.
+///
+/// Useful as a visible boundary between test cases or processing batches when
+/// tailing a log file. The line is logged at [`LoggerBuilder::marker_level`]
+/// (`Info` by default), passes through the global and per-output level filters,
+/// and skips the module filter. No-op if the logger has not been initialized.
+#[track_caller]
+pub fn marker(text: &str) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    let level = logger.marker_level;
+    if !logger.level_match.allows(level, logger.effective_level()) {
+        return;
+    }
+    let caller = std::panic::Location::caller();
+    logger.dispatch(&Record {
+        level,
+        message: &format!("---- {text} ----"),
+        module_path: "",
+        file: caller.file(),
+        line: caller.line(),
+        fields: &[],
+        timestamp: None,
+    });
+    logger.flush_outputs();
+}
+
+/// Logs `message` with structured key/value fields whose keys need not be
+/// known at compile time.
+///
+/// Plain text and logfmt append ` key=value` pairs after the message; JSON
+/// and MessagePack emit them as separate fields. Values are rendered with
+/// `Display`. Records go through the global and per-output level filters.
+/// Module filters are skipped since a function call carries no module path.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// let user = "alice";
+/// let attempts = 3;
+/// nanologger::log_fields(
+///     LogLevel::Warn,
+///     "login failed",
+///     &[("user", &user), ("attempts", &attempts)],
+/// );
+/// // [WARN]  login failed user=alice attempts=3
+/// ```
+#[track_caller]
+pub fn log_fields(level: LogLevel, message: &str, fields: &[(&str, &dyn std::fmt::Display)]) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    if !logger.level_match.allows(level, logger.effective_level()) {
+        return;
+    }
+    let caller = std::panic::Location::caller();
+    let values: Vec<String> = fields.iter().map(|(_, v)| v.to_string()).collect();
+    let fields: Vec<Field<'_>> = fields
+        .iter()
+        .zip(&values)
+        .map(|((key, _), value)| (*key, FieldValue::Str(value)))
+        .collect();
+    logger.dispatch(&Record {
+        level,
+        message,
+        module_path: "",
+        file: caller.file(),
+        line: caller.line(),
+        fields: &fields,
+        timestamp: None,
+    });
+}
+
+/// Hidden public function used by [`log_kv!`]. Do not call directly.
+#[doc(hidden)]
+pub fn __log_kv_with_context(
+    level: LogLevel,
+    message: &str,
+    fields: &[(&str, FieldValue<'_>)],
+    module_path: &str,
+    file: &str,
+    line: u32,
+) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    logger.submit(
+        &Record {
+            level,
+            message,
+            module_path,
+            file,
+            line,
+            fields,
+            timestamp: None,
+        },
+        module_path,
+    );
+}
+
+/// An owned copy of a log record: one captured by [`buffer_early_logs`]
+/// before the logger was initialized, as returned by [`take_preinit_buffer`],
+/// or the record passed to a [`LoggerBuilder::color_rule`] predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub message: String,
+    pub module_path: String,
+    pub file: String,
+    pub line: u32,
+}
+
+/// Bounded pre-init buffer. `None` until [`buffer_early_logs`] enables it.
+struct EarlyBuffer {
+    capacity: usize,
+    records: std::collections::VecDeque<LogRecord>,
+}
+
+static EARLY_BUFFER: std::sync::Mutex<Option<EarlyBuffer>> = std::sync::Mutex::new(None);
+
+/// Captures up to `capacity` log records emitted before initialization and
+/// replays them through the outputs when the logger is initialized.
+///
+/// Without this, macros called before `init` are dropped. Records are replayed
+/// in order and go through the normal level and module filters; timestamps and
+/// thread info reflect the replay, not the original call. When the buffer is
+/// full the oldest record is dropped. Call it first thing in `main`; it has no
+/// effect once the logger is initialized.
+///
+/// ```rust,no_run
+/// nanologger::buffer_early_logs(64);
+/// nanologger::info!("kept until init");
+/// nanologger::init().unwrap(); // replays "kept until init"
+/// ```
+pub fn buffer_early_logs(capacity: usize) {
+    if LOGGER.get().is_some() {
+        return;
+    }
+    if let Ok(mut buffer) = EARLY_BUFFER.lock() {
+        *buffer = Some(EarlyBuffer {
+            capacity,
+            records: std::collections::VecDeque::with_capacity(capacity),
+        });
+    }
+}
+
+/// Stores a pre-init record if [`buffer_early_logs`] enabled buffering.
+fn buffer_early_record(
+    level: LogLevel,
+    message: &str,
+    module_path: &str,
+    file: &str,
+    line: u32,
+) -> bool {
+    let Ok(mut buffer) = EARLY_BUFFER.lock() else {
+        return false;
+    };
+    let Some(buffer) = buffer.as_mut() else {
+        return false;
+    };
+    if buffer.capacity == 0 {
+        return false;
+    }
+    if buffer.records.len() == buffer.capacity {
+        buffer.records.pop_front();
+    }
+    buffer.records.push_back(LogRecord {
+        level,
+        message: message.to_string(),
+        module_path: module_path.to_string(),
+        file: file.to_string(),
+        line,
+    });
+    true
+}
+
+/// In debug builds, tells the developer on stderr, once per process, that a
+/// record was dropped because the logger isn't initialized. Compiled out of
+/// release builds.
+#[cfg(debug_assertions)]
+fn warn_uninitialized(file: &str, line: u32) {
+    static WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+    if !WARNED.swap(true, Ordering::Relaxed) {
+        eprintln!(
+            "nanologger: record from {file}:{line} was dropped because the logger is not \
+             initialized; call nanologger::init() or LoggerBuilder::init() first \
+             (shown once, in debug builds only)"
+        );
+    }
+}
+
+/// Removes and returns the records [`buffer_early_logs`] has captured so far,
+/// oldest first, so they can be examined instead of replayed. Buffering stays
+/// enabled; only records captured after this call are replayed at init.
+///
+/// Returns an empty vec if buffering is disabled or the logger has already
+/// been initialized (which replays and disables the buffer).
+///
+/// ```rust,no_run
+/// nanologger::buffer_early_logs(64);
+/// nanologger::debug!("probing config");
+/// for record in nanologger::take_preinit_buffer() {
+///     eprintln!("early {}: {}", record.level, record.message);
+/// }
+/// ```
+pub fn take_preinit_buffer() -> Vec<LogRecord> {
+    let Ok(mut buffer) = EARLY_BUFFER.lock() else {
+        return Vec::new();
+    };
+    buffer
+        .as_mut()
+        .map(|buffer| buffer.records.drain(..).collect())
+        .unwrap_or_default()
+}
+
+/// Disables the pre-init buffer and logs everything it captured.
+fn replay_early_records(logger: &Logger) {
+    let Some(buffer) = EARLY_BUFFER.lock().ok().and_then(|mut b| b.take()) else {
+        return;
+    };
+    for record in buffer.records {
+        logger.submit(
+            &Record {
+                level: record.level,
+                message: &record.message,
+                module_path: &record.module_path,
+                file: &record.file,
+                line: record.line,
+                fields: &[],
+                timestamp: None,
+            },
+            &record.module_path,
+        );
+    }
+}
+
+/// The kind and level of one configured output, as returned by [`outputs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputInfo {
+    kind: OutputKind,
+    level: LogLevel,
+}
+
+impl OutputInfo {
+    /// The output's kind, as [`LogOutput::kind`].
+    pub fn kind(&self) -> OutputKind {
+        self.kind
+    }
+
+    /// The minimum level the output accepts.
+    pub fn level(&self) -> LogLevel {
+        self.level
+    }
+}
+
+/// Lists every configured output in [`LoggerBuilder::add_output`] order, so
+/// `outputs()[i]` describes the output at index `i` for [`output_bytes`] and
+/// [`output_would_log`].
+///
+/// Returns an empty list if the logger is not initialized.
+pub fn outputs() -> Vec<OutputInfo> {
+    LOGGER
+        .get()
+        .map(|logger| {
+            logger
+                .outputs
+                .iter()
+                .map(|output| OutputInfo {
+                    kind: output.kind(),
+                    level: output.level,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns how many bytes the output at `index` has written since init.
+///
+/// Outputs are indexed in the order they were passed to
+/// [`LoggerBuilder::add_output`]. The default stderr output is index 0 when no
+/// outputs were added; with [`LoggerBuilder::keep_default_term`] it comes
+/// after the added ones.
+/// Returns `None` if the logger is not initialized or `index` is out of range.
+pub fn output_bytes(index: usize) -> Option<u64> {
+    LOGGER
+        .get()
+        .and_then(|logger| logger.outputs.get(index))
+        .map(LogOutput::bytes_written)
+}
+
+/// Returns whether a record at `level` would reach the output at `index` right
+/// now: it must pass both the global level gate (including the calling
+/// thread's [`set_thread_level`] override) and that output's own filter.
+///
+/// Module filters are not considered. Returns `false` if the logger is not
+/// initialized or `index` is out of range.
+pub fn output_would_log(index: usize, level: LogLevel) -> bool {
+    let Some(logger) = LOGGER.get() else {
+        return false;
+    };
+    let Some(output) = logger.outputs.get(index) else {
+        return false;
+    };
+    logger.level_match.allows(level, logger.effective_level())
+        && logger.level_match.allows(level, output.level)
+}
+
+/// Sets the global log level until the returned guard is dropped, then
+/// restores the previous level.
+///
+/// This changes the level process-wide, not just for the calling thread, so
+/// other threads log at the scoped level while the guard is alive. Use
+/// [`set_thread_level`] to change only the current thread. Guards should be
+/// dropped in reverse order of creation.
+///
+/// Has no effect if the logger has not been initialized.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// {
+///     let _trace = nanologger::level_scope(LogLevel::Trace);
+///     nanologger::trace!("visible while the guard is alive");
+/// }
+/// nanologger::trace!("back to the previous level");
+/// ```
+pub fn level_scope(level: LogLevel) -> LevelScopeGuard {
+    let previous = LOGGER.get().map(|logger| logger.level());
+    set_level(level);
+    LevelScopeGuard { previous }
+}
+
+/// Restores the previous global level when dropped. Returned by [`level_scope`].
+#[must_use = "the level is restored as soon as the guard is dropped"]
+pub struct LevelScopeGuard {
+    previous: Option<LogLevel>,
+}
+
+impl Drop for LevelScopeGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            set_level(previous);
+        }
+    }
+}
+
+/// Logs `name: started` at `level`, and `name: completed in 12.3ms` when the
+/// returned guard is dropped. The elapsed time is rendered with
+/// [`fmt::duration`].
+///
+/// Both records go through the usual level filters and outputs, with the
+/// caller's file and line. Module filters are skipped since a function call
+/// carries no module path.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// {
+///     let _timer = nanologger::timed(LogLevel::Debug, "load config");
+///     // ...
+/// } // "load config: completed in 4.2ms"
+/// ```
+#[track_caller]
+pub fn timed(level: LogLevel, name: impl Into<String>) -> TimedGuard {
+    let location = std::panic::Location::caller();
+    let name = name.into();
+    log_from_caller(level, location, &format!("{name}: started"));
+    TimedGuard {
+        level,
+        name,
+        location,
+        start: std::time::Instant::now(),
+    }
+}
+
+/// Logs the completion of a [`timed`] operation when dropped.
+#[must_use = "the completion is logged as soon as the guard is dropped"]
+pub struct TimedGuard {
+    level: LogLevel,
+    name: String,
+    location: &'static std::panic::Location<'static>,
+    start: std::time::Instant,
+}
+
+impl Drop for TimedGuard {
+    fn drop(&mut self) {
+        let elapsed = fmt::duration(self.start.elapsed());
+        let message = format!("{}: completed in {elapsed}", self.name);
+        log_from_caller(self.level, self.location, &message);
+    }
+}
+
+/// Logs `rows` as an aligned ASCII table under `headers`, as one multi-line
+/// record. Each column is padded to its widest cell; missing cells are left
+/// blank. Use [`MultilinePolicy::Split`] to keep the columns aligned under
+/// per-line prefixes.
+///
+/// Level filters apply as usual and nothing is formatted when the level is
+/// filtered out. Module filters are skipped since a function call carries no
+/// module path.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// nanologger::log_table(
+///     LogLevel::Debug,
+///     &["id", "name"],
+///     [[1.to_string(), "alice".into()], [2.to_string(), "bob".into()]],
+/// );
+/// // id | name
+/// // ---+------
+/// // 1  | alice
+/// // 2  | bob
+/// ```
+#[track_caller]
+pub fn log_table<H, R, C>(level: LogLevel, headers: &[H], rows: impl IntoIterator<Item = R>)
+where
+    H: std::fmt::Display,
+    R: IntoIterator<Item = C>,
+    C: std::fmt::Display,
+{
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    if !logger.level_match.allows(level, logger.effective_level()) {
+        return;
+    }
+    let headers: Vec<String> = headers.iter().map(ToString::to_string).collect();
+    let rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|row| row.into_iter().map(|cell| cell.to_string()).collect())
+        .collect();
+    let message = format_table(&headers, &rows);
+    log_from_caller(level, std::panic::Location::caller(), &message);
+}
+
+/// Renders the table text for [`log_table`].
+fn format_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain([headers.len()])
+        .max()
+        .unwrap_or(0);
+    let mut widths = vec![0; columns];
+    for row in std::iter::once(headers).chain(rows.iter().map(Vec::as_slice)) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let render = |cells: &[String]| {
+        let line = widths
+            .iter()
+            .enumerate()
+            .map(|(i, &w)| format!("{:<w$}", cells.get(i).map_or("", String::as_str)))
+            .collect::<Vec<_>>()
+            .join(" | ");
+        line.trim_end().to_string()
+    };
+    let rule = widths
+        .iter()
+        .map(|&w| "-".repeat(w))
+        .collect::<Vec<_>>()
+        .join("-+-");
+    let mut lines = vec![render(headers), rule];
+    lines.extend(rows.iter().map(|row| render(row)));
+    lines.join("\n")
+}
+
+fn log_from_caller(level: LogLevel, location: &std::panic::Location<'_>, message: &str) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    if !logger.level_match.allows(level, logger.effective_level()) {
+        return;
+    }
+    logger.dispatch(&Record {
+        level,
+        message,
+        module_path: "",
+        file: location.file(),
+        line: location.line(),
+        fields: &[],
+        timestamp: None,
+    });
+}
+
+/// Writes `[LEVEL] message` straight to stderr's file descriptor, for signal
+/// handlers, OOM paths, and other places where the normal pipeline could
+/// deadlock or allocate.
+///
+/// Only async-signal-safe operations are used: the line is assembled in a
+/// fixed stack buffer and written with a single `write(2)` to fd 2. That
+/// comes with constraints:
+///
+/// - It bypasses the logger entirely: no level or module filters, no
+///   outputs, formats, timestamps, colors, or hooks. It works before
+///   [`init`] too.
+/// - Lines are capped at 512 bytes including the newline; longer messages
+///   are cut, possibly mid-character.
+/// - Pass a message that is already formatted (e.g. a string literal);
+///   building one with `format!` allocates and defeats the purpose.
+/// - Nothing is retried; a short or failed write is silently dropped.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// extern "C" fn on_sigsegv(_: std::os::raw::c_int) {
+///     nanologger::emergency(LogLevel::Error, "segmentation fault, aborting");
+/// }
+/// ```
+#[cfg(unix)]
+pub fn emergency(level: LogLevel, message: &str) {
+    raw_fd::emergency(level.tag_str(), message);
+}
+
+/// Hidden public function used by the log macros. Do not call directly.
+#[doc(hidden)]
+/// Hidden public function used by the log macros. Do not call directly.
+#[doc(hidden)]
+pub fn __log_with_context(
+    level: LogLevel,
+    message: &str,
+    module_path: &str,
+    file: &str,
+    line: u32,
+) {
+    let Some(logger) = LOGGER.get() else {
+        if !buffer_early_record(level, message, module_path, file, line) {
+            #[cfg(debug_assertions)]
+            warn_uninitialized(file, line);
+        }
+        return;
+    };
+    logger.submit(
+        &Record {
+            level,
+            message,
+            module_path,
+            file,
+            line,
+            fields: &[],
+            timestamp: None,
+        },
+        module_path,
+    );
+}
+
+/// Hidden public function used by the lazy log macros. Do not call directly.
+///
+/// Whether a record at `level` from `module_path` passes the global level gate
+/// and module filter. Always `true` before init, so the record can still be
+/// buffered or trigger the uninitialized warning.
+#[doc(hidden)]
+pub fn __enabled(level: LogLevel, module_path: &str) -> bool {
+    LOGGER
+        .get()
+        .is_none_or(|logger| logger.allows(level, module_path))
+}
+
+/// Logs `message` with `timestamp` in place of the current time, e.g. when
+/// replaying historical events. The text is used verbatim by every output
+/// that shows timestamps; outputs with timestamps off still omit it.
+///
+/// Level filters apply as usual. Module filters are skipped since a function
+/// call carries no module path; the [`log_at!`] macro applies them.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// nanologger::log_at(LogLevel::Info, "user signed in", "2024-03-01 09:15:02");
+/// ```
+#[track_caller]
+pub fn log_at(level: LogLevel, message: &str, timestamp: &str) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    if !logger.level_match.allows(level, logger.effective_level()) {
+        return;
+    }
+    let caller = std::panic::Location::caller();
+    logger.dispatch(&Record {
+        level,
+        message,
+        module_path: "",
+        file: caller.file(),
+        line: caller.line(),
+        fields: &[],
+        timestamp: Some(timestamp),
+    });
+}
+
+/// Hidden public function used by [`log_at!`]. Do not call directly.
+#[doc(hidden)]
+pub fn __log_at_with_context(
+    level: LogLevel,
+    message: &str,
+    timestamp: &str,
+    module_path: &str,
+    file: &str,
+    line: u32,
+) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    logger.submit(
+        &Record {
+            level,
+            message,
+            module_path,
+            file,
+            line,
+            fields: &[],
+            timestamp: Some(timestamp),
+        },
+        module_path,
+    );
+}
+
+/// Hidden public function used by [`audit!`]. Do not call directly.
+#[doc(hidden)]
+pub fn __audit_with_context(message: &str, module_path: &str, file: &str, line: u32) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    let Some(output) = &logger.audit_output else {
+        return;
+    };
+    if !logger.module_allowed(module_path) {
+        return;
+    }
+    logger.dispatch_to(
+        std::slice::from_ref(output),
+        false,
+        &Record {
+            level: LogLevel::Info,
+            message,
+            module_path,
+            file,
+            line,
+            fields: &[],
+            timestamp: None,
+        },
+    );
+}
+
+/// Hidden macro all logging macros expand to. Do not use directly.
+#[doc(hidden)]
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! __log {
+    ($level:expr, $message:expr) => {
+        $crate::__log_with_context($level, $message, module_path!(), file!(), line!())
+    };
+}
+
+/// With the `disabled` feature, logging macros type-check their arguments
+/// but compile to nothing. [`audit!`] is the exception and still logs.
+#[doc(hidden)]
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! __log {
+    ($level:expr, $message:expr) => {
+        if false {
+            let _: ($crate::LogLevel, &str) = ($level, $message);
+        }
+    };
+}
+
+/// Logs a message with typed structured fields.
+///
+/// Each value is converted with [`FieldValue::from`], so integers, floats,
+/// and `bool`s stay typed in JSON and MessagePack output (`"status":200`
+/// rather than `"status":"200"`). Strings pass through as `&str`; use
+/// [`log_fields`] for other `Display` values.
+///
+/// ```rust
+/// use nanologger::LogLevel;
+///
+/// let path = "/health";
+/// nanologger::log_kv!(LogLevel::Info, "request done"; path = path, status = 200, ok = true);
+/// // [INFO]  request done path=/health status=200 ok=true
+/// ```
+#[macro_export]
+macro_rules! log_kv {
+    ($level:expr, $msg:expr; $($key:ident = $value:expr),* $(,)?) => {
+        $crate::__log_kv!(
+            $level,
+            ::core::convert::AsRef::<str>::as_ref(&$msg),
+            &[$((stringify!($key), $crate::FieldValue::from($value))),*]
+        )
+    };
 }
 
-/// Hidden public function used by the log macros. Do not call directly.
-#[doc(hidden)]
-/// Hidden public function used by the log macros. Do not call directly.
 #[doc(hidden)]
-pub fn __log_with_context(
-    level: LogLevel,
-    message: &str,
-    module_path: &str,
-    file: &str,
-    line: u32,
-) {
-    let Some(logger) = LOGGER.get() else {
-        return;
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! __log_kv {
+    ($level:expr, $message:expr, $fields:expr) => {
+        $crate::__log_kv_with_context($level, $message, $fields, module_path!(), file!(), line!())
     };
+}
 
-    // Global level gate
-    if level > logger.level() {
-        return;
-    }
-
-    // Apply module filter
-    if !matches_module_filter(module_path, &logger.module_allow, &logger.module_deny) {
-        return;
-    }
-
-    let ts = if logger.timestamps {
-        Some(format_current_timestamp())
-    } else {
-        None
+#[doc(hidden)]
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! __log_kv {
+    ($level:expr, $message:expr, $fields:expr) => {
+        if false {
+            let _: ($crate::LogLevel, &str, &[(&str, $crate::FieldValue<'_>)]) =
+                ($level, $message, $fields);
+        }
     };
+}
 
-    let source_loc = if logger.source_location {
-        Some((file, line))
-    } else {
-        None
+/// Logs a message with an explicit timestamp; see [`log_at()`]. Unlike the
+/// function, the macro applies module filters.
+///
+/// ```rust
+/// use nanologger::LogLevel;
+///
+/// let (when, user) = ("2024-03-01 09:15:02", "alice");
+/// nanologger::log_at!(LogLevel::Info, when, "user {} signed in", user);
+/// ```
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $timestamp:expr, % $msg:expr) => {
+        $crate::__log_at!(
+            $level,
+            ::core::convert::AsRef::<str>::as_ref(&$msg),
+            ::core::convert::AsRef::<str>::as_ref(&$timestamp)
+        )
     };
+    ($level:expr, $timestamp:expr, $($arg:tt)*) => {
+        $crate::__log_at!(
+            $level,
+            &format!($($arg)*),
+            ::core::convert::AsRef::<str>::as_ref(&$timestamp)
+        )
+    };
+}
 
-    let thread_info_str = if logger.thread_info {
-        let current = std::thread::current();
-        let info = match current.name() {
-            Some(name) => name.to_string(),
-            None => format!("{:?}", current.id()),
-        };
-        Some(info)
-    } else {
-        None
+#[doc(hidden)]
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! __log_at {
+    ($level:expr, $message:expr, $timestamp:expr) => {
+        $crate::__log_at_with_context(
+            $level,
+            $message,
+            $timestamp,
+            module_path!(),
+            file!(),
+            line!(),
+        )
     };
+}
 
-    for output in &logger.outputs {
-        match output {
-            LogOutput::Term { level: out_level } => {
-                if level > *out_level {
-                    continue;
-                }
-                let use_color = std::io::stderr().is_terminal();
-                let formatted = format_message_full(
-                    level,
-                    message,
-                    use_color,
-                    ts.as_deref(),
-                    source_loc,
-                    thread_info_str.as_deref(),
-                );
-                let mut stderr = std::io::stderr().lock();
-                let _ = stderr.write_all(formatted.as_bytes());
-            }
-            LogOutput::Writer {
-                level: out_level,
-                writer,
-            } => {
-                if level > *out_level {
-                    continue;
-                }
-                let formatted = format_message_full(
-                    level,
-                    message,
-                    false,
-                    ts.as_deref(),
-                    source_loc,
-                    thread_info_str.as_deref(),
-                );
-                if let Ok(mut w) = writer.lock() {
-                    let _ = w.write_all(formatted.as_bytes());
-                }
-            }
-            LogOutput::Test { level: out_level } => {
-                if level > *out_level {
-                    continue;
-                }
-                let formatted = format_message_full(
-                    level,
-                    message,
-                    false,
-                    ts.as_deref(),
-                    source_loc,
-                    thread_info_str.as_deref(),
-                );
-                print!("{formatted}");
-            }
+#[doc(hidden)]
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! __log_at {
+    ($level:expr, $message:expr, $timestamp:expr) => {
+        if false {
+            let _: ($crate::LogLevel, &str, &str) = ($level, $message, $timestamp);
         }
-    }
+    };
+}
+
+/// Logs a message at a level chosen at runtime.
+///
+/// ```rust
+/// use nanologger::LogLevel;
+///
+/// let failures = 3;
+/// let level = if failures > 2 { LogLevel::Error } else { LogLevel::Warn };
+/// nanologger::log!(level, "{} consecutive failures", failures);
+/// ```
+#[macro_export]
+macro_rules! log {
+    ($level:expr, % $msg:expr) => {
+        $crate::__log!($level, ::core::convert::AsRef::<str>::as_ref(&$msg))
+    };
+    ($level:expr, $($arg:tt)*) => {
+        $crate::__log!($level, &format!($($arg)*))
+    };
 }
 
 /// Logs a message at the `Error` level.
+///
+/// Every level macro, and [`log!`], also accepts `%message` to log a `&str` or
+/// `String` verbatim, without treating it as a format string:
+///
+/// ```rust
+/// let untrusted = String::from("user typed {braces}");
+/// nanologger::error!(%untrusted);
+/// nanologger::log!(nanologger::LogLevel::Info, %"no {formatting} here");
+/// ```
 #[macro_export]
 macro_rules! error {
+    (% $msg:expr) => {
+        $crate::__log!($crate::LogLevel::Error, ::core::convert::AsRef::<str>::as_ref(&$msg))
+    };
     ($($arg:tt)*) => {
-        $crate::__log_with_context($crate::LogLevel::Error, &format!($($arg)*), module_path!(), file!(), line!())
+        $crate::__log!($crate::LogLevel::Error, &format!($($arg)*))
     };
 }
 
 /// Logs a message at the `Warn` level.
 #[macro_export]
 macro_rules! warn {
+    (% $msg:expr) => {
+        $crate::__log!($crate::LogLevel::Warn, ::core::convert::AsRef::<str>::as_ref(&$msg))
+    };
     ($($arg:tt)*) => {
-        $crate::__log_with_context($crate::LogLevel::Warn, &format!($($arg)*), module_path!(), file!(), line!())
+        $crate::__log!($crate::LogLevel::Warn, &format!($($arg)*))
     };
 }
 
 /// Logs a message at the `Info` level.
 #[macro_export]
 macro_rules! info {
+    (% $msg:expr) => {
+        $crate::__log!($crate::LogLevel::Info, ::core::convert::AsRef::<str>::as_ref(&$msg))
+    };
     ($($arg:tt)*) => {
-        $crate::__log_with_context($crate::LogLevel::Info, &format!($($arg)*), module_path!(), file!(), line!())
+        $crate::__log!($crate::LogLevel::Info, &format!($($arg)*))
     };
 }
 
 /// Logs a message at the `Debug` level.
 #[macro_export]
 macro_rules! debug {
+    (% $msg:expr) => {
+        $crate::__log!($crate::LogLevel::Debug, ::core::convert::AsRef::<str>::as_ref(&$msg))
+    };
     ($($arg:tt)*) => {
-        $crate::__log_with_context($crate::LogLevel::Debug, &format!($($arg)*), module_path!(), file!(), line!())
+        $crate::__log!($crate::LogLevel::Debug, &format!($($arg)*))
     };
 }
 
 /// Logs a message at the `Trace` level.
 #[macro_export]
 macro_rules! trace {
+    (% $msg:expr) => {
+        $crate::__log!($crate::LogLevel::Trace, ::core::convert::AsRef::<str>::as_ref(&$msg))
+    };
+    ($($arg:tt)*) => {
+        $crate::__log!($crate::LogLevel::Trace, &format!($($arg)*))
+    };
+}
+
+/// Logs the result of a closure, running it only if the record passes the
+/// global level gate and module filter.
+///
+/// The other macros format their arguments before the level check, so an
+/// expensive argument (hashing a buffer, rendering a tree) is paid for even
+/// when the record is dropped. Here the closure runs after the check, and its
+/// `Display` result becomes the message. Per-output levels, rate limits and
+/// throttling apply later, so a record that passes the gate runs the closure
+/// even if no output ends up writing it.
+///
+/// ```rust
+/// use nanologger::LogLevel;
+///
+/// let payload = vec![0u8; 4096];
+/// nanologger::log_lazy!(LogLevel::Debug, || format!("payload sum {}", payload.iter().map(|&b| b as u64).sum::<u64>()));
+/// ```
+#[macro_export]
+macro_rules! log_lazy {
+    ($level:expr, $f:expr) => {
+        $crate::__log_lazy!($level, $f)
+    };
+}
+
+/// Logs the result of a closure at the `Debug` level; see [`log_lazy!`].
+#[macro_export]
+macro_rules! debug_lazy {
+    ($f:expr) => {
+        $crate::__log_lazy!($crate::LogLevel::Debug, $f)
+    };
+}
+
+/// Logs the result of a closure at the `Trace` level; see [`log_lazy!`].
+#[macro_export]
+macro_rules! trace_lazy {
+    ($f:expr) => {
+        $crate::__log_lazy!($crate::LogLevel::Trace, $f)
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "disabled"))]
+#[macro_export]
+macro_rules! __log_lazy {
+    ($level:expr, $f:expr) => {{
+        let level: $crate::LogLevel = $level;
+        if $crate::__enabled(level, module_path!()) {
+            $crate::__log!(level, &::std::string::ToString::to_string(&($f)()))
+        }
+    }};
+}
+
+#[doc(hidden)]
+#[cfg(feature = "disabled")]
+#[macro_export]
+macro_rules! __log_lazy {
+    ($level:expr, $f:expr) => {
+        if false {
+            let _: $crate::LogLevel = $level;
+            let _ = ::std::string::ToString::to_string(&($f)());
+        }
+    };
+}
+
+/// Logs an audit record to the output set with [`LoggerBuilder::audit_output`].
+///
+/// Audit records bypass the global level gate, including [`set_level`] and
+/// [`set_thread_level`], so they are kept even when the app runs quietly. They
+/// still honor module filters, are formatted as `Info` records, and are a
+/// no-op when no audit output is configured.
+///
+/// Unlike the other logging macros, `audit!` is not compiled out by the
+/// `disabled` feature, so audit trails survive builds that strip diagnostic
+/// logging.
+///
+/// ```rust,no_run
+/// use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+///
+/// LoggerBuilder::new()
+///     .level(LogLevel::Error)
+///     .audit_output(LogOutput::file(LogLevel::Trace, "audit.log").unwrap())
+///     .init()
+///     .unwrap();
+///
+/// nanologger::audit!("user {} deleted project {}", "alice", 42);
+/// ```
+#[macro_export]
+macro_rules! audit {
+    (% $msg:expr) => {
+        $crate::__audit_with_context(::core::convert::AsRef::<str>::as_ref(&$msg), module_path!(), file!(), line!())
+    };
     ($($arg:tt)*) => {
-        $crate::__log_with_context($crate::LogLevel::Trace, &format!($($arg)*), module_path!(), file!(), line!())
+        $crate::__audit_with_context(&format!($($arg)*), module_path!(), file!(), line!())
     };
 }
 
+/// Logs a titled block of aligned `key: value` lines as a single record.
+///
+/// Takes a level, a title, and any iterator of `(key, value)` pairs where the
+/// key is `AsRef<str>` and the value implements `Display`. Keys are padded so
+/// the values line up. The block goes through the normal level gate, module
+/// filter, and outputs like any other message.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// nanologger::banner!(LogLevel::Info, "config", [
+///     ("host", "0.0.0.0".to_string()),
+///     ("port", 8080.to_string()),
+///     ("workers", 4.to_string()),
+/// ]);
+/// // [INFO]  config
+/// //   host:    0.0.0.0
+/// //   port:    8080
+/// //   workers: 4
+/// ```
+#[macro_export]
+macro_rules! banner {
+    ($level:expr, $title:expr, $pairs:expr $(,)?) => {
+        $crate::__log!($level, &$crate::__format_banner($title, $pairs))
+    };
+}
+
+/// Logs an error and its full [`source()`](std::error::Error::source) chain as
+/// a single record.
+///
+/// Each underlying cause is emitted on its own indented `caused by:` line.
+/// Logs at `Error` level by default; pass a level first to override it.
+///
+/// ```rust,no_run
+/// use nanologger::LogLevel;
+///
+/// let err = std::fs::read("missing.toml").unwrap_err();
+/// nanologger::error_chain!(&err);
+/// nanologger::error_chain!(LogLevel::Warn, &err);
+/// ```
+#[macro_export]
+macro_rules! error_chain {
+    ($level:expr, $err:expr $(,)?) => {
+        $crate::__log!($level, &$crate::__format_error_chain($err))
+    };
+    ($err:expr $(,)?) => {
+        $crate::error_chain!($crate::LogLevel::Error, $err)
+    };
+}
+
+/// Hidden public function used by the [`error_chain!`] macro. Do not call directly.
+///
+/// Formats `err` followed by one indented `caused by:` line per source.
+#[doc(hidden)]
+pub fn __format_error_chain(err: &dyn std::error::Error) -> String {
+    let mut out = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        out.push_str(&format!("\n  caused by: {cause}"));
+        source = cause.source();
+    }
+    out
+}
+
+/// Hidden public function used by the [`banner!`] macro. Do not call directly.
+///
+/// Formats `title` followed by one indented `key: value` line per pair, with
+/// keys right-padded so values align.
+#[doc(hidden)]
+pub fn __format_banner<I, K, V>(title: &str, pairs: I) -> String
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: std::fmt::Display,
+{
+    let pairs: Vec<(K, V)> = pairs.into_iter().collect();
+    let width = pairs
+        .iter()
+        .map(|(k, _)| k.as_ref().chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = title.to_string();
+    for (key, value) in &pairs {
+        let key = format!("{}:", key.as_ref());
+        out.push_str(&format!("\n  {key:<w$} {value}", w = width + 1));
+    }
+    out
+}
+
 // ---------------------------------------------------------------------------
 // Log facade integration (feature = "log")
 // ---------------------------------------------------------------------------
 
 #[cfg(feature = "log")]
-impl LogLevel {
-    /// Converts a `log::Level` to a `LogLevel`.
-    fn from_log_level(level: log::Level) -> Self {
+impl From<log::Level> for LogLevel {
+    fn from(level: log::Level) -> Self {
         match level {
             log::Level::Error => LogLevel::Error,
             log::Level::Warn => LogLevel::Warn,
@@ -653,15 +5792,40 @@ impl LogLevel {
             log::Level::Trace => LogLevel::Trace,
         }
     }
+}
 
-    /// Converts a `LogLevel` to a `log::LevelFilter`.
-    fn to_log_level_filter(self) -> log::LevelFilter {
-        match self {
-            LogLevel::Error => log::LevelFilter::Error,
-            LogLevel::Warn => log::LevelFilter::Warn,
-            LogLevel::Info => log::LevelFilter::Info,
-            LogLevel::Debug => log::LevelFilter::Debug,
-            LogLevel::Trace => log::LevelFilter::Trace,
+#[cfg(feature = "log")]
+impl From<LogLevel> for log::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => log::Level::Error,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Trace => log::Level::Trace,
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<LogLevel> for log::LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        log::Level::from(level).to_level_filter()
+    }
+}
+
+#[cfg(feature = "log")]
+impl Logger {
+    /// Returns the most verbose level the facade needs to forward under the
+    /// configured [`LevelMatch`]. Once a thread-level override exists, every
+    /// level is forwarded and [`log::Log::enabled`] does the filtering.
+    fn facade_max_level(&self) -> log::LevelFilter {
+        if THREAD_LEVEL_USED.load(Ordering::Relaxed) {
+            return log::LevelFilter::Trace;
+        }
+        match self.level_match {
+            LevelMatch::Range(a, b) => log::LevelFilter::from(a.max(b)),
+            LevelMatch::AtLeast | LevelMatch::Exact => log::LevelFilter::from(self.level()),
         }
     }
 }
@@ -669,103 +5833,35 @@ impl LogLevel {
 #[cfg(feature = "log")]
 impl log::Log for Logger {
     fn enabled(&self, metadata: &log::Metadata) -> bool {
-        let level = LogLevel::from_log_level(metadata.level());
-        if level > self.level() {
-            return false;
-        }
         // target() defaults to module_path in the log crate
-        matches_module_filter(metadata.target(), &self.module_allow, &self.module_deny)
+        self.allows(LogLevel::from(metadata.level()), metadata.target())
     }
 
     fn log(&self, record: &log::Record) {
-        if !self.enabled(record.metadata()) {
+        let level = LogLevel::from(record.level());
+        // Checked before formatting the message, which `submit` can't do.
+        if !self.allows(level, record.target()) {
             return;
         }
-
-        let level = LogLevel::from_log_level(record.level());
         let message = format!("{}", record.args());
-        let file = record.file().unwrap_or("");
-        let line = record.line().unwrap_or(0);
-
-        let ts = if self.timestamps {
-            Some(format_current_timestamp())
-        } else {
-            None
-        };
-
-        let source_loc = if self.source_location {
-            Some((file, line))
-        } else {
-            None
-        };
-
-        let thread_info_str = if self.thread_info {
-            let current = std::thread::current();
-            let info = match current.name() {
-                Some(name) => name.to_string(),
-                None => format!("{:?}", current.id()),
-            };
-            Some(info)
-        } else {
-            None
-        };
-
-        for output in &self.outputs {
-            match output {
-                LogOutput::Term { level: out_level } => {
-                    if level > *out_level {
-                        continue;
-                    }
-                    let use_color = std::io::stderr().is_terminal();
-                    let formatted = format_message_full(
-                        level,
-                        &message,
-                        use_color,
-                        ts.as_deref(),
-                        source_loc,
-                        thread_info_str.as_deref(),
-                    );
-                    let mut stderr = std::io::stderr().lock();
-                    let _ = stderr.write_all(formatted.as_bytes());
-                }
-                LogOutput::Writer {
-                    level: out_level,
-                    writer,
-                } => {
-                    if level > *out_level {
-                        continue;
-                    }
-                    let formatted = format_message_full(
-                        level,
-                        &message,
-                        false,
-                        ts.as_deref(),
-                        source_loc,
-                        thread_info_str.as_deref(),
-                    );
-                    if let Ok(mut w) = writer.lock() {
-                        let _ = w.write_all(formatted.as_bytes());
-                    }
-                }
-                LogOutput::Test { level: out_level } => {
-                    if level > *out_level {
-                        continue;
-                    }
-                    let formatted = format_message_full(
-                        level,
-                        &message,
-                        false,
-                        ts.as_deref(),
-                        source_loc,
-                        thread_info_str.as_deref(),
-                    );
-                    print!("{formatted}");
-                }
-            }
-        }
+        self.dispatch(&Record {
+            level,
+            message: &message,
+            module_path: if self.format.split_target {
+                record.target()
+            } else {
+                record.module_path().unwrap_or(record.target())
+            },
+            file: record.file().unwrap_or(""),
+            line: record.line().unwrap_or(0),
+            fields: &[],
+            timestamp: None,
+        });
     }
 
-    fn flush(&self) {}
+    fn flush(&self) {
+        self.flush_outputs();
+    }
 }
 
 #[cfg(test)]
@@ -801,56 +5897,410 @@ mod tests {
 
     #[test]
     #[serial]
-    fn test_warn_color_is_yellow_bold() {
+    fn test_warn_color_is_yellow_bold() {
+        nanocolor::set_colors_override(true);
+        let output = format_message(LogLevel::Warn, "caution", true);
+        assert!(
+            output.contains("\x1b[1;33m"),
+            "Warn prefix should be bold yellow, got: {:?}",
+            output
+        );
+        nanocolor::clear_colors_override();
+    }
+
+    #[test]
+    #[serial]
+    fn test_info_color_is_green_bold() {
+        nanocolor::set_colors_override(true);
+        let output = format_message(LogLevel::Info, "ok", true);
+        assert!(
+            output.contains("\x1b[1;32m"),
+            "Info prefix should be bold green, got: {:?}",
+            output
+        );
+        nanocolor::clear_colors_override();
+    }
+
+    #[test]
+    #[serial]
+    fn test_debug_color_is_blue_bold() {
+        nanocolor::set_colors_override(true);
+        let output = format_message(LogLevel::Debug, "details", true);
+        assert!(
+            output.contains("\x1b[1;34m"),
+            "Debug prefix should be bold blue, got: {:?}",
+            output
+        );
+        nanocolor::clear_colors_override();
+    }
+
+    #[test]
+    #[serial]
+    fn test_trace_color_is_magenta_bold() {
+        nanocolor::set_colors_override(true);
+        let output = format_message(LogLevel::Trace, "verbose", true);
+        assert!(
+            output.contains("\x1b[1;35m"),
+            "Trace prefix should be bold magenta, got: {:?}",
+            output
+        );
+        nanocolor::clear_colors_override();
+    }
+
+    #[test]
+    #[serial]
+    fn test_message_dim_below() {
+        nanocolor::set_colors_override(true);
+        let opts = FormatOptions {
+            message_color: MessageColorPolicy::DimBelow(LogLevel::Info),
+            ..FormatOptions::default()
+        };
+        let debug = format_line(&opts, LogLevel::Debug, "quiet", true, &Segments::default());
+        assert!(
+            debug.ends_with(" \x1b[2mquiet\x1b[0m\n"),
+            "Debug message should be dimmed, got: {debug:?}"
+        );
+        let warn = format_line(&opts, LogLevel::Warn, "loud", true, &Segments::default());
+        assert!(
+            warn.ends_with(" loud\n"),
+            "Warn message should not be dimmed, got: {warn:?}"
+        );
+        let plain = format_line(&opts, LogLevel::Debug, "quiet", false, &Segments::default());
+        assert_eq!(plain, "[DEBUG] quiet\n");
+        nanocolor::clear_colors_override();
+    }
+
+    #[test]
+    #[serial]
+    fn test_message_per_level_color() {
         nanocolor::set_colors_override(true);
-        let output = format_message(LogLevel::Warn, "caution", true);
+        let theme = ColorTheme::new().with(LogLevel::Error, Color::Red);
+        let opts = FormatOptions {
+            message_color: MessageColorPolicy::PerLevel(theme),
+            ..FormatOptions::default()
+        };
+        let error = format_line(&opts, LogLevel::Error, "boom", true, &Segments::default());
         assert!(
-            output.contains("\x1b[1;33m"),
-            "Warn prefix should be bold yellow, got: {:?}",
-            output
+            error.ends_with(" \x1b[31mboom\x1b[0m\n"),
+            "Error message should be red, got: {error:?}"
         );
+        let info = format_line(&opts, LogLevel::Info, "ok", true, &Segments::default());
+        assert!(info.ends_with(" ok\n"), "Info has no theme color: {info:?}");
         nanocolor::clear_colors_override();
     }
 
     #[test]
     #[serial]
-    fn test_info_color_is_green_bold() {
+    fn test_level_colors_extended() {
         nanocolor::set_colors_override(true);
-        let output = format_message(LogLevel::Info, "ok", true);
+        let theme = ColorTheme::new()
+            .with(LogLevel::Trace, ThemeColor::Rgb(120, 120, 120))
+            .with(LogLevel::Debug, ThemeColor::Ansi256(208));
+        let mut opts = FormatOptions {
+            level_colors: theme,
+            color_depth: ColorDepth::TrueColor,
+            ..FormatOptions::default()
+        };
+        let trace = format_line(&opts, LogLevel::Trace, "t", true, &Segments::default());
+        assert_eq!(trace, "\x1b[1;38;2;120;120;120m[TRACE]\x1b[0m t\n");
+        let debug = format_line(&opts, LogLevel::Debug, "d", true, &Segments::default());
+        assert_eq!(debug, "\x1b[1;38;5;208m[DEBUG]\x1b[0m d\n");
+        let info = format_line(&opts, LogLevel::Info, "i", true, &Segments::default());
         assert!(
-            output.contains("\x1b[1;32m"),
-            "Info prefix should be bold green, got: {:?}",
-            output
+            info.starts_with("\x1b[1;32m[INFO]"),
+            "default color kept: {info:?}"
         );
+
+        opts.color_depth = ColorDepth::Ansi256;
+        let trace = format_line(&opts, LogLevel::Trace, "t", true, &Segments::default());
+        assert_eq!(trace, "\x1b[1;38;5;102m[TRACE]\x1b[0m t\n");
+
+        opts.color_depth = ColorDepth::Basic;
+        let trace = format_line(&opts, LogLevel::Trace, "t", true, &Segments::default());
+        assert_eq!(trace, "\x1b[1;90m[TRACE]\x1b[0m t\n");
+        let plain = format_line(&opts, LogLevel::Trace, "t", false, &Segments::default());
+        assert_eq!(plain, "[TRACE] t\n");
         nanocolor::clear_colors_override();
     }
 
     #[test]
-    #[serial]
-    fn test_debug_color_is_blue_bold() {
-        nanocolor::set_colors_override(true);
-        let output = format_message(LogLevel::Debug, "details", true);
-        assert!(
-            output.contains("\x1b[1;34m"),
-            "Debug prefix should be bold blue, got: {:?}",
-            output
+    fn test_json_format() {
+        let opts = FormatOptions {
+            output_format: OutputFormat::Json,
+            app: Some("svc@1.0".to_string()),
+            ..FormatOptions::default()
+        };
+        let segments = Segments {
+            sequence: Some(7),
+            timestamp: Some("12:00:00.000"),
+            thread_info: Some("main"),
+            source_loc: Some(("src/main.rs", 3)),
+            crate_tag: Some("svc"),
+            module: None,
+            line_style: None,
+            fields: &[],
+            repeat_meta: false,
+        };
+        let line = format_line(&opts, LogLevel::Warn, "say \"hi\"\n", true, &segments);
+        assert_eq!(
+            line,
+            "{\"seq\":7,\"ts\":\"12:00:00.000\",\"thread\":\"main\",\"level\":\"warn\",\"app\":\"svc@1.0\",\"file\":\"src/main.rs\",\"line\":3,\"msg\":\"say \\\"hi\\\"\\n\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_source_location_format() {
+        let segments = Segments {
+            source_loc: Some(("src/net/tcp.rs", 12)),
+            ..Segments::default()
+        };
+        let mut opts = FormatOptions::default();
+        let mut check = |format: SourceLocationFormat, expected: &str| {
+            opts.source_format = format;
+            let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+            assert_eq!(line, format!("[INFO]  {expected} hi\n"));
+        };
+        check(SourceLocationFormat::new(), "[src/net/tcp.rs:12]");
+        check(
+            SourceLocationFormat::new().delimiters("", ""),
+            "src/net/tcp.rs:12",
+        );
+        check(
+            SourceLocationFormat::new()
+                .delimiters("@", "")
+                .basename(true),
+            "@tcp.rs:12",
+        );
+        check(
+            SourceLocationFormat::new().separator(" line "),
+            "[src/net/tcp.rs line 12]",
+        );
+    }
+
+    #[test]
+    fn test_hidden_level_tag() {
+        let opts = FormatOptions {
+            show_level_tag: false,
+            ..FormatOptions::default()
+        };
+        let segments = Segments {
+            timestamp: Some("12:00:00.000"),
+            thread_info: Some("main"),
+            ..Segments::default()
+        };
+        let line = format_line(&opts, LogLevel::Warn, "hi", false, &segments);
+        assert_eq!(line, "12:00:00.000 (main) hi\n");
+        let bare = format_line(&opts, LogLevel::Warn, "hi", false, &Segments::default());
+        assert_eq!(bare, "hi\n");
+        let json = FormatOptions {
+            output_format: OutputFormat::Json,
+            ..opts
+        };
+        let line = format_line(&json, LogLevel::Warn, "hi", false, &Segments::default());
+        assert_eq!(line, "{\"level\":\"warn\",\"msg\":\"hi\"}\n");
+    }
+
+    #[test]
+    fn test_hostname_follows_app() {
+        let mut opts = FormatOptions {
+            app: Some("svc".to_string()),
+            host: Some("web-1".to_string()),
+            ..FormatOptions::default()
+        };
+        let segments = Segments::default();
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(line, "[INFO]  svc web-1 hi\n");
+        opts.output_format = OutputFormat::Json;
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(
+            line,
+            "{\"level\":\"info\",\"app\":\"svc\",\"host\":\"web-1\",\"msg\":\"hi\"}\n"
+        );
+        opts.output_format = OutputFormat::Logfmt;
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(line, "level=info app=svc host=web-1 msg=hi\n");
+    }
+
+    #[test]
+    fn test_build_id_follows_host() {
+        let mut opts = FormatOptions {
+            app: Some("svc@1.2.0".to_string()),
+            host: Some("web-1".to_string()),
+            build: Some("3f9c2ab".to_string()),
+            ..FormatOptions::default()
+        };
+        let segments = Segments::default();
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(line, "[INFO]  svc@1.2.0 web-1 3f9c2ab hi\n");
+        opts.output_format = OutputFormat::Json;
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(
+            line,
+            "{\"level\":\"info\",\"app\":\"svc@1.2.0\",\"host\":\"web-1\",\"build\":\"3f9c2ab\",\"msg\":\"hi\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_detected_host_only_in_structured_output() {
+        let mut opts = FormatOptions {
+            host: Some("web-1".to_string()),
+            host_detected: true,
+            ..FormatOptions::default()
+        };
+        let segments = Segments::default();
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(
+            line,
+            "[INFO]  hi
+"
+        );
+        opts.output_format = OutputFormat::Logfmt;
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(
+            line,
+            "level=info host=web-1 msg=hi
+"
+        );
+    }
+
+    #[cfg(feature = "hostname")]
+    #[test]
+    fn test_system_hostname() {
+        assert!(system_hostname().is_some_and(|host| !host.is_empty()));
+    }
+
+    #[test]
+    fn test_fields_in_every_format() {
+        let count = 3;
+        let fields: [Field<'_>; 4] = [
+            ("user", FieldValue::Str("bob smith")),
+            ("count", FieldValue::from(count)),
+            ("ratio", FieldValue::F64(0.5)),
+            ("ok", FieldValue::Bool(true)),
+        ];
+        let segments = Segments {
+            fields: &fields,
+            ..Segments::default()
+        };
+        let mut opts = FormatOptions::default();
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(
+            line,
+            "[INFO]  hi user=\"bob smith\" count=3 ratio=0.5 ok=true\n"
+        );
+        opts.output_format = OutputFormat::Json;
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(
+            line,
+            "{\"level\":\"info\",\"msg\":\"hi\",\"user\":\"bob smith\",\"count\":3,\"ratio\":0.5,\"ok\":true}\n"
+        );
+        opts.output_format = OutputFormat::Logfmt;
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(
+            line,
+            "level=info msg=hi user=\"bob smith\" count=3 ratio=0.5 ok=true\n"
+        );
+
+        let fields = [("x", FieldValue::F64(f64::NAN)), ("n", FieldValue::I64(-2))];
+        let segments = Segments {
+            fields: &fields,
+            ..Segments::default()
+        };
+        opts.output_format = OutputFormat::Json;
+        let line = format_line(&opts, LogLevel::Info, "hi", false, &segments);
+        assert_eq!(
+            line,
+            "{\"level\":\"info\",\"msg\":\"hi\",\"x\":null,\"n\":-2}\n"
         );
-        nanocolor::clear_colors_override();
     }
 
     #[test]
     #[serial]
-    fn test_trace_color_is_magenta_bold() {
+    fn test_syslog_numeric_level_style() {
         nanocolor::set_colors_override(true);
-        let output = format_message(LogLevel::Trace, "verbose", true);
-        assert!(
-            output.contains("\x1b[1;35m"),
-            "Trace prefix should be bold magenta, got: {:?}",
-            output
-        );
+        let opts = FormatOptions {
+            level_style: LevelStyle::SyslogNumeric,
+            ..FormatOptions::default()
+        };
+        let segments = Segments {
+            timestamp: Some("12:00:00.000"),
+            ..Segments::default()
+        };
+        let line = format_line(&opts, LogLevel::Error, "disk full", true, &segments);
+        assert_eq!(line, "<3>12:00:00.000 disk full\n");
+        let line = format_line(&opts, LogLevel::Info, "ok", false, &Segments::default());
+        assert_eq!(line, "<6>ok\n");
         nanocolor::clear_colors_override();
     }
 
+    #[test]
+    fn test_multiline_policies() {
+        let segments = Segments {
+            sequence: Some(1),
+            ..Segments::default()
+        };
+        let mut opts = FormatOptions::default();
+        let raw = format_line(&opts, LogLevel::Info, "a\nb", false, &segments);
+        assert_eq!(raw, "#000001 [INFO]  a\nb\n");
+
+        opts.multiline = MultilinePolicy::Escape;
+        let escaped = format_line(&opts, LogLevel::Info, "a\r\nb", false, &segments);
+        assert_eq!(escaped, "#000001 [INFO]  a\\r\\nb\n");
+
+        opts.multiline = MultilinePolicy::Split;
+        let split = format_line(&opts, LogLevel::Info, "a\nb\n", false, &segments);
+        assert_eq!(split, "#000001 [INFO]  a\n#000001 [INFO]  b\n");
+
+        opts.trailing_newline = false;
+        let split = format_line(&opts, LogLevel::Info, "a\nb", false, &segments);
+        assert_eq!(split, "#000001 [INFO]  a\n#000001 [INFO]  b");
+
+        opts.output_format = OutputFormat::Json;
+        let json = format_line(&opts, LogLevel::Info, "a\nb", false, &Segments::default());
+        assert_eq!(json, "{\"level\":\"info\",\"msg\":\"a\\nb\"}");
+    }
+
+    #[test]
+    fn test_logfmt_format() {
+        let opts = FormatOptions {
+            output_format: OutputFormat::Logfmt,
+            ..FormatOptions::default()
+        };
+        let line = format_line(&opts, LogLevel::Info, "a=b c", false, &Segments::default());
+        assert_eq!(line, "level=info msg=\"a=b c\"\n");
+        let line = format_line(&opts, LogLevel::Error, "boom", false, &Segments::default());
+        assert_eq!(line, "level=error msg=boom\n");
+    }
+
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!("JSON".parse(), Ok(OutputFormat::Json));
+        assert_eq!("logfmt".parse(), Ok(OutputFormat::Logfmt));
+        assert_eq!("Plain".parse(), Ok(OutputFormat::Plain));
+        let err = "yaml".parse::<OutputFormat>().unwrap_err();
+        assert_eq!(err.input(), "yaml");
+    }
+
+    #[test]
+    fn test_theme_color_nearest_basic() {
+        assert_eq!(
+            ThemeColor::Rgb(250, 10, 10).nearest_basic(),
+            Color::BrightRed
+        );
+        assert_eq!(ThemeColor::Ansi256(4).nearest_basic(), Color::Blue);
+        assert_eq!(ThemeColor::Ansi256(232).nearest_basic(), Color::Black);
+        assert_eq!(ThemeColor::Ansi256(231).nearest_basic(), Color::BrightWhite);
+    }
+
+    #[test]
+    fn test_paint_message_inner_styles_take_precedence() {
+        let message = "a \x1b[1;36mb\x1b[0m c";
+        assert_eq!(
+            paint_message(message, "2"),
+            "\x1b[2ma \x1b[0m\x1b[1;36mb\x1b[0m\x1b[2m c\x1b[0m"
+        );
+    }
+
     #[test]
     fn test_plain_mode_no_ansi() {
         let output = format_message(LogLevel::Error, "test", false);
@@ -941,6 +6391,154 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_line_ending_crlf() {
+        let opts = FormatOptions {
+            line_ending: LineEnding::CrLf,
+            ..FormatOptions::default()
+        };
+        let output = format_line(&opts, LogLevel::Info, "hi", false, &Segments::default());
+        assert_eq!(output, "[INFO]  hi\r\n");
+    }
+
+    #[test]
+    fn test_no_trailing_newline() {
+        let opts = FormatOptions {
+            line_ending: LineEnding::CrLf,
+            trailing_newline: false,
+            ..FormatOptions::default()
+        };
+        let output = format_line(&opts, LogLevel::Info, "hi", false, &Segments::default());
+        assert_eq!(output, "[INFO]  hi");
+    }
+
+    #[test]
+    fn test_app_segment_after_tag() {
+        let opts = FormatOptions {
+            app: Some("svc@1.2.3".to_string()),
+            ..FormatOptions::default()
+        };
+        let segments = Segments {
+            timestamp: Some("00:00:00.000"),
+            source_loc: Some(("main.rs", 7)),
+            ..Segments::default()
+        };
+        let output = format_line(&opts, LogLevel::Warn, "hi", false, &segments);
+        assert_eq!(output, "00:00:00.000 [WARN]  svc@1.2.3 [main.rs:7] hi\n");
+    }
+
+    #[test]
+    fn test_sequence_number_prepended() {
+        let segments = Segments {
+            sequence: Some(123),
+            timestamp: Some("00:00:00.000"),
+            ..Segments::default()
+        };
+        let output = format_line(
+            &FormatOptions::default(),
+            LogLevel::Info,
+            "hi",
+            false,
+            &segments,
+        );
+        assert_eq!(output, "#000123 00:00:00.000 [INFO]  hi\n");
+    }
+
+    #[test]
+    fn test_line_ending_platform() {
+        let expected = if cfg!(windows) { "\r\n" } else { "\n" };
+        assert_eq!(LineEnding::Platform.as_str(), expected);
+        assert_eq!(LineEnding::default(), LineEnding::Lf);
+    }
+
+    #[test]
+    fn test_format_banner_aligns_values() {
+        let out = __format_banner("config", [("host", "0.0.0.0"), ("workers", "4")]);
+        assert_eq!(out, "config\n  host:    0.0.0.0\n  workers: 4");
+    }
+
+    #[test]
+    fn test_format_banner_no_pairs() {
+        let out = __format_banner("empty", Vec::<(&str, &str)>::new());
+        assert_eq!(out, "empty");
+    }
+
+    #[derive(Debug)]
+    struct Layer(&'static str, Option<Box<Layer>>);
+
+    impl std::fmt::Display for Layer {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl std::error::Error for Layer {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            self.1.as_deref().map(|e| e as _)
+        }
+    }
+
+    #[test]
+    fn test_format_error_chain_walks_sources() {
+        let err = Layer(
+            "load config",
+            Some(Box::new(Layer(
+                "read file",
+                Some(Box::new(Layer("permission denied", None))),
+            ))),
+        );
+        assert_eq!(
+            __format_error_chain(&err),
+            "load config\n  caused by: read file\n  caused by: permission denied"
+        );
+        assert_eq!(__format_error_chain(&Layer("alone", None)), "alone");
+    }
+
+    // ── fmt helper unit tests ──
+
+    #[test]
+    fn test_fmt_duration_units() {
+        use std::time::Duration;
+        assert_eq!(
+            fmt::duration(Duration::from_millis(1250)).to_string(),
+            "1.25s"
+        );
+        assert_eq!(fmt::duration(Duration::from_secs(2)).to_string(), "2s");
+        assert_eq!(
+            fmt::duration(Duration::from_millis(350)).to_string(),
+            "350ms"
+        );
+        assert_eq!(
+            fmt::duration(Duration::from_nanos(12_500)).to_string(),
+            "12.5µs"
+        );
+        assert_eq!(
+            fmt::duration(Duration::from_nanos(800)).to_string(),
+            "800ns"
+        );
+        assert_eq!(fmt::duration(Duration::ZERO).to_string(), "0ns");
+    }
+
+    #[test]
+    fn test_fmt_rounding_carries_into_next_unit() {
+        use std::time::Duration;
+        let duration = |nanos| fmt::duration(Duration::from_nanos(nanos)).to_string();
+        assert_eq!(duration(999_999_999), "1s");
+        assert_eq!(duration(999_999), "1ms");
+        assert_eq!(duration(999_994_999), "999.99ms");
+        assert_eq!(fmt::bytes(1_048_575).to_string(), "1.0 MiB");
+        assert_eq!(fmt::bytes(1_048_524).to_string(), "1023.9 KiB");
+    }
+
+    #[test]
+    fn test_fmt_bytes_units() {
+        assert_eq!(fmt::bytes(0).to_string(), "0 B");
+        assert_eq!(fmt::bytes(1023).to_string(), "1023 B");
+        assert_eq!(fmt::bytes(1024).to_string(), "1.0 KiB");
+        assert_eq!(fmt::bytes(1_468_006).to_string(), "1.4 MiB");
+        assert_eq!(fmt::bytes(u64::MAX).to_string(), "16.0 EiB");
+    }
+
     // ── format_message property tests ──
 
     proptest! {
@@ -1142,6 +6740,397 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_throttle_forgets_least_recent_key() {
+        let throttle = Throttle {
+            window: std::time::Duration::from_secs(60),
+            key: ThrottleKey::Message,
+            entries: Default::default(),
+        };
+        let messages: Vec<String> = (0..=THROTTLE_CAPACITY).map(|i| i.to_string()).collect();
+        let record = |message| Record {
+            level: LogLevel::Info,
+            message,
+            module_path: "m",
+            file: "f.rs",
+            line: 1,
+            fields: &[],
+            timestamp: None,
+        };
+        assert!(matches!(
+            throttle.check(&record("0")),
+            ThrottleDecision::Emit
+        ));
+        assert!(matches!(
+            throttle.check(&record("1")),
+            ThrottleDecision::Emit
+        ));
+        // Touch "0" so that "1" is the least recently seen.
+        assert!(matches!(
+            throttle.check(&record("0")),
+            ThrottleDecision::Suppress
+        ));
+        for message in &messages[2..] {
+            throttle.check(&record(message));
+        }
+        assert!(matches!(
+            throttle.check(&record("0")),
+            ThrottleDecision::Suppress
+        ));
+        assert!(matches!(
+            throttle.check(&record("1")),
+            ThrottleDecision::Emit
+        ));
+    }
+
+    #[test]
+    fn test_encoding_bytes() {
+        assert_eq!(&*Encoding::Utf8.encode("é\n", true), "é\n".as_bytes());
+        assert_eq!(
+            &*Encoding::Utf8Bom.encode("é\n", true),
+            b"\xEF\xBB\xBF\xC3\xA9\n"
+        );
+        assert_eq!(&*Encoding::Utf8Bom.encode("é\n", false), b"\xC3\xA9\n");
+        assert_eq!(
+            &*Encoding::Utf16Le.encode("é\n", true),
+            b"\xFF\xFE\xE9\x00\n\x00"
+        );
+        assert_eq!(&*Encoding::Utf16Le.encode("€", false), b"\xAC\x20");
+    }
+
+    #[test]
+    fn test_log_output_kind() {
+        assert!(matches!(
+            LogOutput::term(LogLevel::Info).kind(),
+            OutputKind::Term
+        ));
+        assert_eq!(
+            LogOutput::writer(LogLevel::Info, std::io::sink()).kind(),
+            OutputKind::Writer
+        );
+        assert_eq!(
+            LogOutput::tee(LogLevel::Info, Vec::new()).kind(),
+            OutputKind::Tee
+        );
+        assert_eq!(LogOutput::test(LogLevel::Info).kind(), OutputKind::Test);
+        let (buffer, _) = LogOutput::buffer(LogLevel::Info, 4);
+        assert_eq!(buffer.kind(), OutputKind::Buffer);
+    }
+
+    #[test]
+    fn test_log_output_debug() {
+        let output = LogOutput::term(LogLevel::Info)
+            .fallback(LogOutput::writer(LogLevel::Warn, std::io::sink()));
+        assert_eq!(
+            format!("{output:?}"),
+            "Term { level: Info, fallback: Writer { level: Warn } }"
+        );
+        let (buffer, _) = LogOutput::buffer(LogLevel::Trace, 4);
+        assert_eq!(format!("{buffer:?}"), "Buffer { level: Trace }");
+        assert_eq!(
+            format!("{:?}", LogOutput::tee(LogLevel::Debug, Vec::new())),
+            "Tee { level: Debug }"
+        );
+        assert_eq!(
+            format!("{:?}", LogOutput::test(LogLevel::Error)),
+            "Test { level: Error }"
+        );
+    }
+
+    #[test]
+    fn test_split_target_keys() {
+        let opts = FormatOptions {
+            output_format: OutputFormat::Logfmt,
+            ..FormatOptions::default()
+        };
+        let segments = Segments {
+            module: Some("myapp::db::pool"),
+            ..Default::default()
+        };
+        let line = format_line(&opts, LogLevel::Info, "ready", false, &segments);
+        assert_eq!(
+            line,
+            "level=info crate=myapp module=myapp::db::pool msg=ready\n"
+        );
+    }
+
+    #[test]
+    fn test_module_filter_entry_problem() {
+        for valid in [
+            "myapp",
+            "myapp::db",
+            "my_app::v2",
+            "hyper",
+            "my-crate",
+            "hyper.client",
+        ] {
+            assert_eq!(module_filter_entry_problem(valid), None, "{valid}");
+        }
+        assert_eq!(
+            module_filter_entry_problem(""),
+            Some("it is empty, so it matches every module")
+        );
+        assert_eq!(
+            module_filter_entry_problem(" myapp"),
+            Some("it has surrounding whitespace")
+        );
+        for empty_segment in ["myapp::", "::myapp", "myapp::::db"] {
+            assert_eq!(
+                module_filter_entry_problem(empty_segment),
+                Some("it has an empty `::` segment"),
+                "{empty_segment}"
+            );
+        }
+        for invalid in ["myapp:db", "myapp/db", "myapp::*", "my app"] {
+            assert!(module_filter_entry_problem(invalid).is_some(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_line_style_replaces_level_colors() {
+        let opts = FormatOptions {
+            color: ColorChoice::Always,
+            ..Default::default()
+        };
+        let segments = Segments {
+            line_style: Some(LineStyle::new(Color::BrightRed).bold()),
+            ..Default::default()
+        };
+        let line = format_line(&opts, LogLevel::Info, "SLA breach", true, &segments);
+        assert_eq!(line, "\x1b[1;91m[INFO]  SLA breach\x1b[0m\n");
+        let line = format_line(&opts, LogLevel::Info, "SLA breach", false, &segments);
+        assert_eq!(line, "[INFO]  SLA breach\n");
+    }
+
+    #[test]
+    fn test_fixed_ts_level_field() {
+        let opts = FormatOptions {
+            ts_level_field: Some(TsLevelField {
+                timestamp: 12,
+                tag: 8,
+                overflow: Default::default(),
+            }),
+            ..Default::default()
+        };
+        let line = |ts, level| {
+            let segments = Segments {
+                timestamp: Some(ts),
+                thread_info: Some("main"),
+                ..Default::default()
+            };
+            let mut out = String::new();
+            format_plain_into(&mut out, &opts, level, "m", false, &segments);
+            out
+        };
+        let columnar = [
+            line("09:15:04.120", LogLevel::Info),
+            line("09:15:04.121", LogLevel::Error),
+            line("09:15:04.122", LogLevel::Warn),
+        ];
+        assert_eq!(
+            columnar,
+            [
+                "09:15:04.120 [INFO]  (main) m",
+                "09:15:04.121 [ERROR] (main) m",
+                "09:15:04.122 [WARN]  (main) m",
+            ]
+        );
+        // A wider timestamp, e.g. from `log_at!`, widens the field from then on.
+        assert_eq!(
+            line("2024-03-01 09:15:04", LogLevel::Warn),
+            "2024-03-01 09:15:04 [WARN]  (main) m"
+        );
+        assert_eq!(
+            line("09:15:05.000", LogLevel::Info),
+            "09:15:05.000 [INFO]         (main) m"
+        );
+    }
+
+    #[test]
+    fn test_message_alignment() {
+        let segments = |loc| Segments {
+            source_loc: Some(loc),
+            ..Default::default()
+        };
+        let mut opts = FormatOptions {
+            align: MessageAlign::Column(24),
+            color: ColorChoice::Always,
+            ..Default::default()
+        };
+        let mut out = String::new();
+        format_plain_into(
+            &mut out,
+            &opts,
+            LogLevel::Info,
+            "a",
+            true,
+            &segments(("m.rs", 1)),
+        );
+        assert_eq!(out, "\x1b[1;32m[INFO] \x1b[0m [m.rs:1]        a");
+        out.clear();
+        let long = ("src/very/long/path.rs", 120);
+        format_plain_into(&mut out, &opts, LogLevel::Info, "b", false, &segments(long));
+        assert_eq!(out, "[INFO]  [src/very/long/path.rs:120] b");
+
+        opts.align = MessageAlign::Auto(Default::default());
+        let mut lines = Vec::new();
+        for loc in [("m.rs", 1), ("lib.rs", 10), ("m.rs", 2)] {
+            let mut out = String::new();
+            format_plain_into(&mut out, &opts, LogLevel::Info, "x", false, &segments(loc));
+            lines.push(out);
+        }
+        assert_eq!(
+            lines,
+            [
+                "[INFO]  [m.rs:1] x",
+                "[INFO]  [lib.rs:10] x",
+                "[INFO]  [m.rs:2]    x"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_summary() {
+        let mut stats = LogStats::default();
+        assert_eq!(
+            format_summary(&stats),
+            (
+                LogLevel::Info,
+                "nanologger: 0 errors, 0 warnings during run (0 records)".to_string()
+            )
+        );
+        stats.counts = [0, 1, 5, 0, 0];
+        assert_eq!(
+            format_summary(&stats),
+            (
+                LogLevel::Warn,
+                "nanologger: 0 errors, 1 warning during run (6 records)".to_string()
+            )
+        );
+        stats.counts = [12, 48, 1000, 200, 42];
+        assert_eq!(
+            format_summary(&stats),
+            (
+                LogLevel::Error,
+                "nanologger: 12 errors, 48 warnings during run (1302 records)".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_table() {
+        let cells = |row: &[&str]| row.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let table = format_table(
+            &cells(&["id", "name"]),
+            &[
+                cells(&["1", "alice"]),
+                cells(&["22"]),
+                cells(&["3", "bob", "extra"]),
+            ],
+        );
+        assert_eq!(
+            table,
+            "id | name  |\n\
+             ---+-------+------\n\
+             1  | alice |\n\
+             22 |       |\n\
+             3  | bob   | extra"
+        );
+        assert_eq!(format_table(&[], &[]), "\n");
+    }
+
+    #[test]
+    fn test_offset_timestamp() {
+        let noon = 12 * 3600 * 1_000_000_000 + 5_000_000;
+        assert_eq!(offset_timestamp(noon, 0), "12:00:00.005");
+        assert_eq!(offset_timestamp(noon, 120), "14:00:00.005");
+        assert_eq!(offset_timestamp(noon, -330), "06:30:00.005");
+        assert_eq!(offset_timestamp(noon, 13 * 60), "01:00:00.005");
+    }
+
+    #[test]
+    fn test_rate_limiter_burst_then_refill() {
+        let limiter = RateLimiter::new(1000, 5);
+        let allowed = (0..20).filter(|_| limiter.try_acquire()).count();
+        assert!((5..=6).contains(&allowed), "allowed {allowed}");
+        assert_eq!(limiter.take_summary(), Some(20 - allowed as u64));
+        // A second summary within the interval is suppressed.
+        limiter.dropped.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(limiter.take_summary(), None);
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(limiter.try_acquire(), "tokens refill over time");
+    }
+
+    #[test]
+    fn test_prefix_set_matching() {
+        let set = PrefixSet::new(vec!["a::b".into(), "c".into(), "a".into(), "a::bc".into()]);
+        assert_eq!(set, PrefixSet::new(vec!["c".into(), "a".into()]));
+        assert!(set.has_prefix_of("a::x"));
+        assert!(!set.has_prefix_of("b::y"));
+        assert!(set.has_prefix_of("cc"));
+
+        let set = PrefixSet::new(vec!["app::db".into(), "app::dbx".into(), "app::web".into()]);
+        assert!(set.has_prefix_of("app::db::pool"));
+        assert!(set.has_prefix_of("app::web"));
+        assert!(!set.has_prefix_of("app::d"));
+        assert!(!set.has_prefix_of("app::dc"));
+        assert!(!set.has_prefix_of("app::"));
+
+        assert!(PrefixSet::new(vec!["".into(), "z".into()]).has_prefix_of("anything"));
+        assert!(!PrefixSet::default().has_prefix_of(""));
+    }
+
+    #[test]
+    fn test_module_filter_parse_spec() {
+        let filter = ModuleFilter::parse(" app::db , -app::db::pool,, -noisy ", false);
+        assert_eq!(
+            filter,
+            ModuleFilter::new(
+                vec!["app::db".into()],
+                vec!["app::db::pool".into(), "noisy".into()]
+            )
+        );
+        assert_eq!(ModuleFilter::parse("", false), ModuleFilter::default());
+
+        let filter = ModuleFilter::parse("MyApp::DB,-myapp::db::Pool", true);
+        assert!(filter.matches("myapp::db"));
+        assert!(filter.matches("MYAPP::Db::conn"));
+        assert!(!filter.matches("MyApp::DB::POOL"));
+        assert!(!filter.matches("other"));
+        assert!(!ModuleFilter::parse("MyApp", false).matches("myapp"));
+    }
+
+    // ── level match unit tests ──
+
+    #[test]
+    fn test_level_match_at_least() {
+        assert!(LevelMatch::AtLeast.allows(LogLevel::Error, LogLevel::Info));
+        assert!(LevelMatch::AtLeast.allows(LogLevel::Info, LogLevel::Info));
+        assert!(!LevelMatch::AtLeast.allows(LogLevel::Debug, LogLevel::Info));
+    }
+
+    #[test]
+    fn test_level_match_exact() {
+        assert!(LevelMatch::Exact.allows(LogLevel::Debug, LogLevel::Debug));
+        assert!(!LevelMatch::Exact.allows(LogLevel::Error, LogLevel::Debug));
+        assert!(!LevelMatch::Exact.allows(LogLevel::Trace, LogLevel::Debug));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_level_match_range_ignores_threshold_and_bound_order(
+            level in arb_log_level(),
+            threshold in arb_log_level(),
+            a in arb_log_level(),
+            b in arb_log_level(),
+        ) {
+            let expected = level >= a.min(b) && level <= a.max(b);
+            prop_assert_eq!(LevelMatch::Range(a, b).allows(level, threshold), expected);
+            prop_assert_eq!(LevelMatch::Range(b, a).allows(level, threshold), expected);
+        }
+    }
+
     // ── Property 5: set_level then level() consistency ──
     // Feature: env-and-runtime-level, Property 5: set_level then level() consistency
 
@@ -1151,11 +7140,33 @@ mod tests {
         fn prop_set_level_then_level_consistency(level in arb_log_level()) {
             let logger = Logger {
                 level: AtomicU8::new(LogLevel::Info.as_u8()),
+                level_match: LevelMatch::AtLeast,
                 timestamps: false,
+                clock: None,
+                utc_offset: None,
+                timestamp_from: LogLevel::Trace,
                 source_location: false,
                 thread_info: false,
-                module_allow: Vec::new(),
-                module_deny: Vec::new(),
+                thread_id_style: ThreadIdStyle::Debug,
+                sequence_numbers: false,
+                sequence: AtomicU64::new(0),
+                modules: std::sync::RwLock::new(ModuleFilter::default()),
+                base_modules: ModuleFilter::default(),
+                reload_on_sighup: false,
+                format: FormatOptions::default(),
+                marker_level: LogLevel::Info,
+                summary_on_shutdown: false,
+                audit_output: None,
+                skip_empty: false,
+                flush_on: None,
+                rate_limit: None,
+                throttle: None,
+                transform: None,
+                color_rule: None,
+                dispatch_lock: None,
+                compact: false,
+                counts: Default::default(),
+                reporter: std::sync::Mutex::new(None),
                 outputs: Vec::new(),
             };
             logger.level.store(level.as_u8(), Ordering::Relaxed);
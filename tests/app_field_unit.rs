@@ -0,0 +1,46 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `app_name` and `app_version` render as `name@version` after the level tag.
+#[test]
+fn test_app_name_and_version_segment() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .app_name("billing")
+        .app_version(env!("CARGO_PKG_VERSION"))
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Info, "charged", "test_mod", "test.rs", 1);
+
+    assert_eq!(
+        buf_reader.contents(),
+        format!("[INFO]  billing@{} charged\n", env!("CARGO_PKG_VERSION"))
+    );
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "test-util")]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+
+/// Quiet closures pass through their result; warnings and errors fail the
+/// assertion with the offending lines, and other threads are ignored.
+#[test]
+fn test_assert_no_logs_above() {
+    LoggerBuilder::new()
+        .level(LogLevel::Debug)
+        .add_output(LogOutput::writer(LogLevel::Trace, std::io::sink()))
+        .init()
+        .expect("init should succeed");
+
+    let value = nanologger::assert_no_logs_above(LogLevel::Info, || {
+        nanologger::info!("fine");
+        nanologger::debug!("also fine");
+        std::thread::spawn(|| nanologger::error!("another thread"))
+            .join()
+            .unwrap();
+        42
+    });
+    assert_eq!(value, 42);
+
+    let panic = std::panic::catch_unwind(|| {
+        nanologger::assert_no_logs_above(LogLevel::Info, || {
+            nanologger::info!("fine");
+            nanologger::warn!("disk {}% full", 91);
+            nanologger::error!("boom");
+        })
+    })
+    .expect_err("warnings should fail the assertion");
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        message,
+        "expected no logs above INFO, got 2:\n  [WARN]  disk 91% full\n  [ERROR] boom"
+    );
+
+    nanologger::assert_no_logs_above(LogLevel::Warn, || nanologger::warn!("allowed"));
+}
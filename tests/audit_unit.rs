@@ -0,0 +1,60 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `audit!` bypasses the global gate, goes only to the audit output, and
+/// honors module filters.
+#[test]
+fn test_audit_bypasses_level_gate() {
+    // No-op before init.
+    nanologger::audit!("before init");
+
+    let main = SharedBuf::new();
+    let audit = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Error)
+        .module_deny(vec!["audit_unit::denied".to_string()])
+        .add_output(LogOutput::writer(LogLevel::Trace, main.clone()))
+        .audit_output(LogOutput::writer(LogLevel::Error, audit.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("filtered out");
+    nanologger::audit!("user {} logged in", "alice");
+    let text = String::from("verbatim {}");
+    nanologger::audit!(%text);
+    denied::log();
+
+    assert_eq!(
+        audit.contents(),
+        "[INFO]  user alice logged in\n[INFO]  verbatim {}\n"
+    );
+    assert!(main.contents().is_empty());
+}
+
+mod denied {
+    pub fn log() {
+        nanologger::audit!("from a denied module");
+    }
+}
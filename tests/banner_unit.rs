@@ -0,0 +1,54 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `banner!` emits one record with aligned values and respects the level gate.
+#[test]
+fn test_banner_single_record_and_level_gate() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::banner!(
+        LogLevel::Info,
+        "startup",
+        [("host", "0.0.0.0".to_string()), ("port", 8080.to_string())],
+    );
+    assert_eq!(
+        buf_reader.contents(),
+        "[INFO]  startup\n  host: 0.0.0.0\n  port: 8080\n"
+    );
+
+    buf_reader.0.lock().unwrap().clear();
+    nanologger::banner!(LogLevel::Debug, "hidden", [("k", 1)]);
+    assert!(
+        buf_reader.contents().is_empty(),
+        "Banner below the global level should be discarded"
+    );
+}
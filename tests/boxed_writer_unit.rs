@@ -0,0 +1,44 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A writer picked at runtime behind a `Box<dyn Write + Send>` receives lines.
+#[test]
+fn test_from_boxed_writer() {
+    let buf = SharedBuf::new();
+    let use_buffer = std::env::args().count() > 0;
+    let writer: Box<dyn Write + Send> = if use_buffer {
+        Box::new(buf.clone())
+    } else {
+        Box::new(std::io::sink())
+    };
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::from_boxed_writer(LogLevel::Trace, writer))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("boxed");
+    assert_eq!(buf.contents(), "[INFO]  boxed\n");
+}
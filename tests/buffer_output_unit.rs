@@ -0,0 +1,38 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+
+/// The buffer keeps the newest lines, and subscribers get new lines live,
+/// missing some only when they fall behind.
+#[test]
+fn test_buffer_history_and_subscribers() {
+    let (output, handle) = LogOutput::buffer(LogLevel::Info, 2);
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(output)
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("one");
+    let live = handle.subscribe();
+    let slow = handle.subscribe();
+    let gone = handle.subscribe();
+    drop(gone);
+    nanologger::info!("two");
+    nanologger::debug!("filtered");
+    nanologger::warn!("three");
+
+    assert_eq!(handle.lines(), ["[INFO]  two", "[WARN]  three"]);
+    assert_eq!(
+        live.try_iter().collect::<Vec<_>>(),
+        ["[INFO]  two", "[WARN]  three"]
+    );
+
+    // `slow` still holds two undelivered lines, so the next one is dropped
+    // for it alone.
+    nanologger::error!("four");
+    assert_eq!(live.try_recv().unwrap(), "[ERROR] four");
+    assert_eq!(
+        slow.try_iter().collect::<Vec<_>>(),
+        ["[INFO]  two", "[WARN]  three"]
+    );
+    assert_eq!(handle.lines(), ["[WARN]  three", "[ERROR] four"]);
+}
@@ -0,0 +1,34 @@
+use nanologger::{Encoding, LogLevel, LogOutput, LoggerBuilder};
+
+fn utf16le(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+/// With an encoding, only the file is encoded: the ring keeps UTF-8 lines, and
+/// appending to a non-empty file doesn't write a second byte order mark.
+#[test]
+fn test_buffered_and_file_encodes_only_the_file() {
+    let path = std::env::temp_dir().join(format!(
+        "nanologger-buffered-encoding-{}.log",
+        std::process::id()
+    ));
+    let mut existing = vec![0xFF, 0xFE];
+    existing.extend(utf16le("[INFO]  earlier run\n"));
+    std::fs::write(&path, &existing).unwrap();
+
+    let (output, recent) =
+        LogOutput::buffered_and_file(LogLevel::Info, 4, &path).expect("file should open");
+    LoggerBuilder::new()
+        .add_output(output.encoding(Encoding::Utf16Le))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("caf\u{e9}");
+
+    let file = std::fs::read(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let mut expected = existing;
+    expected.extend(utf16le("[INFO]  caf\u{e9}\n"));
+    assert_eq!(file, expected);
+    assert_eq!(recent.lines(), ["[INFO]  caf\u{e9}"]);
+}
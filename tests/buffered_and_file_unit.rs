@@ -0,0 +1,27 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+
+/// The ring and the file receive the same formatted lines, and the ring
+/// keeps only the newest `capacity`.
+#[test]
+fn test_buffered_and_file_share_lines() {
+    let path = std::env::temp_dir().join(format!("nanologger-buffered-{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let (output, recent) =
+        LogOutput::buffered_and_file(LogLevel::Info, 2, &path).expect("file should open");
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(output)
+        .init()
+        .expect("init should succeed");
+
+    nanologger::debug!("below the output level");
+    nanologger::info!("one");
+    nanologger::warn!("two");
+    nanologger::error!("three");
+
+    let file = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(file, "[INFO]  one\n[WARN]  two\n[ERROR] three\n");
+    assert_eq!(recent.lines(), vec!["[WARN]  two", "[ERROR] three"]);
+}
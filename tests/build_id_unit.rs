@@ -0,0 +1,48 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, OutputFormat};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The build id appears in every record as a `build` key.
+#[test]
+fn test_build_id_in_every_line() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .output_format(OutputFormat::Json)
+        .app_name("svc")
+        .hostname("")
+        .build_id("3f9c2ab")
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("one");
+    nanologger::error!("two");
+
+    assert_eq!(
+        buf.contents(),
+        "{\"level\":\"info\",\"app\":\"svc\",\"build\":\"3f9c2ab\",\"msg\":\"one\"}\n\
+         {\"level\":\"error\",\"app\":\"svc\",\"build\":\"3f9c2ab\",\"msg\":\"two\"}\n"
+    );
+}
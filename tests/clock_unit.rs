@@ -0,0 +1,47 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A caller-supplied clock replaces the real one, so timestamps are exact.
+#[test]
+fn test_fixed_clock_timestamp() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .timestamps(true)
+        .clock(|| "00:00:00.000".to_string())
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Info, "first", "test_mod", "test.rs", 1);
+    nanologger::__log_with_context(LogLevel::Error, "second", "test_mod", "test.rs", 2);
+
+    assert_eq!(
+        buf_reader.contents(),
+        "00:00:00.000 [INFO]  first\n00:00:00.000 [ERROR] second\n"
+    );
+}
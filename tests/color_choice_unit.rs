@@ -0,0 +1,44 @@
+use nanologger::{ColorChoice, LoggerBuilder};
+
+/// Runs this test binary's `test_name` with `__NANOLOG_COLOR_CHECK=choice`
+/// and returns what it wrote to stderr, which is a pipe rather than a TTY.
+fn child_stderr(test_name: &str, choice: &str) -> String {
+    let exe = std::env::current_exe().expect("current_exe");
+    let output = std::process::Command::new(exe)
+        .arg(test_name)
+        .arg("--exact")
+        .arg("--nocapture")
+        .env("__NANOLOG_COLOR_CHECK", choice)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to spawn subprocess");
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+/// Test that the Term output `init` injects when no outputs are configured
+/// honors the builder's color choice, even when stderr is piped.
+#[test]
+fn test_color_choice_reaches_injected_term() {
+    if let Ok(choice) = std::env::var("__NANOLOG_COLOR_CHECK") {
+        let choice = match choice.as_str() {
+            "always" => ColorChoice::Always,
+            _ => ColorChoice::Never,
+        };
+        LoggerBuilder::new()
+            .color(choice)
+            .init()
+            .expect("init should succeed");
+        nanologger::info!("colored?");
+        return;
+    }
+
+    let forced = child_stderr("test_color_choice_reaches_injected_term", "always");
+    assert!(
+        forced.contains("\x1b[1;32m[INFO] \x1b[0m colored?"),
+        "expected a colored tag, got: {forced:?}"
+    );
+
+    let never = child_stderr("test_color_choice_reaches_injected_term", "never");
+    assert!(never.contains("[INFO]  colored?"), "got: {never:?}");
+    assert!(!never.contains('\x1b'), "got: {never:?}");
+}
@@ -0,0 +1,58 @@
+use nanologger::{Color, ColorChoice, LineStyle, LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A matching rule restyles the whole colored line; other lines keep their
+/// level colors and plain writers stay uncolored.
+#[test]
+fn test_color_rule_highlights_matching_lines() {
+    let term = SharedBuf::new();
+    let plain = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .color(ColorChoice::Always)
+        .color_rule(|record| {
+            record
+                .message
+                .contains("SLA breach")
+                .then(|| LineStyle::new(Color::BrightRed).bold())
+        })
+        .add_output(LogOutput::term(LogLevel::Trace).term_writer(Box::new(term.clone())))
+        .add_output(LogOutput::writer(LogLevel::Trace, plain.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("SLA breach on checkout");
+    nanologger::info!("all good");
+
+    assert_eq!(
+        term.contents(),
+        "\x1b[1;91m[INFO]  SLA breach on checkout\x1b[0m\n\
+         \x1b[1;32m[INFO] \x1b[0m all good\n"
+    );
+    assert_eq!(
+        plain.contents(),
+        "[INFO]  SLA breach on checkout\n[INFO]  all good\n"
+    );
+}
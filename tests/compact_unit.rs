@@ -0,0 +1,85 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Repeated timestamps (to the second) and thread info are blanked per
+/// output; a new second or thread prints them again.
+#[test]
+fn test_compact_blanks_repeated_metadata() {
+    let all = SharedBuf::new();
+    let warn = SharedBuf::new();
+    let times = Arc::new(Mutex::new(
+        vec![
+            "12:00:00.001",
+            "12:00:00.500",
+            "12:00:00.900",
+            "12:00:01.000",
+            "12:00:01.200",
+        ]
+        .into_iter(),
+    ));
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .timestamps(true)
+        .thread_info(true)
+        .compact(true)
+        .clock(move || times.lock().unwrap().next().unwrap().to_string())
+        .add_output(LogOutput::writer(LogLevel::Trace, all.clone()))
+        .add_output(LogOutput::writer(LogLevel::Warn, warn.clone()).thread_info(false))
+        .init()
+        .expect("init should succeed");
+
+    std::thread::Builder::new()
+        .name("m".into())
+        .spawn(|| {
+            nanologger::info!("one");
+            nanologger::warn!("two");
+            std::thread::Builder::new()
+                .name("w".into())
+                .spawn(|| nanologger::info!("three"))
+                .unwrap()
+                .join()
+                .unwrap();
+            nanologger::warn!("four");
+            nanologger::warn!("five");
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(
+        all.contents(),
+        "12:00:00.001 (m) [INFO]  one\n\
+         \x20                [WARN]  two\n\
+         12:00:00.900 (w) [INFO]  three\n\
+         12:00:01.000 (m) [WARN]  four\n\
+         \x20                [WARN]  five\n"
+    );
+    assert_eq!(
+        warn.contents(),
+        "12:00:00.500 [WARN]  two\n\
+         12:00:01.000 [WARN]  four\n\
+         \x20            [WARN]  five\n"
+    );
+}
@@ -0,0 +1,50 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that the first module path segment is shown after the level tag.
+#[test]
+fn test_crate_tag_after_level() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .crate_tag(true)
+        .app_name("svc")
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Info, "from db", "myapp::db::pool", "db.rs", 1);
+    nanologger::__log_with_context(LogLevel::Warn, "top level", "tool", "main.rs", 1);
+    nanologger::log_fields(LogLevel::Info, "no module", &[]);
+    nanologger::info!("macro");
+    assert_eq!(
+        buf_reader.contents(),
+        "[INFO]  [myapp] svc from db\n\
+         [WARN]  [tool] svc top level\n\
+         [INFO]  svc no module\n\
+         [INFO]  [crate_tag_unit] svc macro\n"
+    );
+}
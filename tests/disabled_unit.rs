@@ -0,0 +1,53 @@
+#![cfg(feature = "disabled")]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::sync::{Arc, Mutex};
+
+/// With the `disabled` feature the logging macros compile to nothing: their
+/// arguments are never evaluated and no output is written. `audit!` is the
+/// exception and still reaches the audit output.
+#[test]
+fn test_disabled_macros_are_no_ops() {
+    let lines = Arc::new(Mutex::new(Vec::<String>::new()));
+    let sink = lines.clone();
+    let audited = Arc::new(Mutex::new(Vec::<String>::new()));
+    let audit_sink = audited.clone();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::test_with(LogLevel::Trace, move |line| {
+            sink.lock().unwrap().push(line.to_string());
+        }))
+        .audit_output(LogOutput::test_with(LogLevel::Trace, move |line| {
+            audit_sink.lock().unwrap().push(line.to_string());
+        }))
+        .init()
+        .expect("init should succeed");
+
+    let mut evaluated = false;
+    let mut touch = || {
+        evaluated = true;
+        1
+    };
+    nanologger::error!("{}", touch());
+    nanologger::warn!("warn");
+    nanologger::info!(%String::from("verbatim"));
+    nanologger::debug!("debug");
+    nanologger::trace!("trace");
+    nanologger::log!(LogLevel::Info, "dynamic");
+    nanologger::log_kv!(LogLevel::Info, "kv"; n = 1, ok = true);
+    nanologger::log_at!(LogLevel::Info, "12:00:00.000", "at {}", touch());
+    nanologger::banner!(LogLevel::Info, "title", [("k", 1)]);
+    nanologger::error_chain!(&std::io::Error::other("boom"));
+    nanologger::debug_lazy!(|| format!("lazy {}", touch()));
+    nanologger::log_lazy!(LogLevel::Info, || touch() + 1);
+
+    assert!(!evaluated);
+    assert!(lines.lock().unwrap().is_empty());
+
+    nanologger::audit!("user {} deleted project {}", "alice", 42);
+    assert_eq!(
+        *audited.lock().unwrap(),
+        ["[INFO]  user alice deleted project 42
+"]
+    );
+}
@@ -0,0 +1,54 @@
+use nanologger::{DispatchOrdering, LogLevel, LogOutput, LoggerBuilder};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// In strict mode every output sees concurrent records in the same order, and
+/// an output that logs from inside its sink does not deadlock.
+#[test]
+fn test_strict_ordering_is_consistent_across_outputs() {
+    let first = Arc::new(Mutex::new(Vec::<String>::new()));
+    let second = Arc::new(Mutex::new(Vec::<String>::new()));
+    let nested = AtomicBool::new(false);
+    let (a, b) = (first.clone(), second.clone());
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .ordering(DispatchOrdering::Strict)
+        .add_output(LogOutput::test_with(LogLevel::Trace, move |line| {
+            a.lock().unwrap().push(line.to_string());
+        }))
+        .add_output(LogOutput::test_with(LogLevel::Trace, move |line| {
+            if !nested.swap(true, Ordering::SeqCst) {
+                nanologger::info!("nested");
+            }
+            b.lock().unwrap().push(line.to_string());
+        }))
+        .init()
+        .expect("init should succeed");
+
+    let threads: Vec<_> = (0..8)
+        .map(|t| {
+            std::thread::spawn(move || {
+                for i in 0..50 {
+                    nanologger::info!("thread {t} record {i}");
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    // The nested record is written mid-dispatch, so compare the rest.
+    let outer = |lines: &[String]| -> Vec<String> {
+        lines
+            .iter()
+            .filter(|l| !l.contains("nested"))
+            .cloned()
+            .collect()
+    };
+    let first = first.lock().unwrap();
+    let second = second.lock().unwrap();
+    assert_eq!(first.len(), 8 * 50 + 1);
+    assert_eq!(second.len(), 8 * 50 + 1);
+    assert_eq!(outer(&first), outer(&second));
+}
@@ -0,0 +1,24 @@
+use nanologger::{InitError, LogLevel, LogOutput, LoggerBuilder};
+
+/// Two `Term` outputs are rejected at init unless explicitly allowed.
+#[test]
+fn test_duplicate_term_outputs_rejected_unless_allowed() {
+    let err = LoggerBuilder::new()
+        .add_output(LogOutput::term(LogLevel::Info))
+        .add_output(LogOutput::term(LogLevel::Error))
+        .init()
+        .expect_err("duplicate term outputs should be rejected");
+    assert!(
+        matches!(err, InitError::DuplicateOutput("stderr")),
+        "Expected DuplicateOutput, got: {err:?}"
+    );
+    assert!(err.to_string().contains("allow_duplicate_outputs"));
+
+    // The failed init must not have claimed the global logger.
+    LoggerBuilder::new()
+        .add_output(LogOutput::term(LogLevel::Info))
+        .add_output(LogOutput::term(LogLevel::Error))
+        .allow_duplicate_outputs(true)
+        .init()
+        .expect("duplicates should be accepted when allowed");
+}
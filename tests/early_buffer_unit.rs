@@ -0,0 +1,49 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Records logged before init are replayed on init, newest `capacity` only,
+/// and still pass the level filter.
+#[test]
+fn test_early_logs_replayed_on_init() {
+    nanologger::info!("before buffering, dropped");
+    nanologger::buffer_early_logs(3);
+    nanologger::info!("one");
+    nanologger::info!("two");
+    nanologger::debug!("three, below level");
+    nanologger::warn!("four");
+
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+    nanologger::info!("after init");
+
+    assert_eq!(
+        buf.contents(),
+        "[INFO]  two\n[WARN]  four\n[INFO]  after init\n"
+    );
+}
@@ -0,0 +1,37 @@
+#![cfg(unix)]
+
+use nanologger::LogLevel;
+
+/// Runs this test binary's `test_name` with `__NANOLOG_EMERGENCY_CHECK` set
+/// and returns what it wrote to stderr.
+fn child_stderr(test_name: &str) -> String {
+    let exe = std::env::current_exe().expect("current_exe");
+    let output = std::process::Command::new(exe)
+        .arg(test_name)
+        .arg("--exact")
+        .arg("--nocapture")
+        .env("__NANOLOG_EMERGENCY_CHECK", "1")
+        .output()
+        .expect("failed to spawn subprocess");
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+/// Emergency lines reach stderr without an initialized logger, and long
+/// messages are cut to the fixed line size.
+#[test]
+fn test_emergency_writes_to_stderr() {
+    if std::env::var_os("__NANOLOG_EMERGENCY_CHECK").is_some() {
+        nanologger::emergency(LogLevel::Error, "out of memory");
+        nanologger::emergency(LogLevel::Warn, &"x".repeat(1000));
+        return;
+    }
+
+    let stderr = child_stderr("test_emergency_writes_to_stderr");
+    assert!(
+        stderr.contains("[ERROR] out of memory\n"),
+        "got: {stderr:?}"
+    );
+    let long = format!("[WARN]  {}\n", "x".repeat(512 - 9));
+    assert!(stderr.contains(&long), "got: {stderr:?}");
+    assert!(!stderr.contains(&"x".repeat(512 - 8)), "got: {stderr:?}");
+}
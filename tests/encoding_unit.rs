@@ -0,0 +1,71 @@
+use nanologger::{Encoding, LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Byte order marks are written once, and only at the start of a file;
+/// UTF-16 outputs encode every line.
+#[test]
+fn test_output_encodings() {
+    let dir = std::env::temp_dir();
+    let fresh = dir.join(format!("nanologger-bom-fresh-{}.log", std::process::id()));
+    let existing = dir.join(format!(
+        "nanologger-bom-existing-{}.log",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&fresh);
+    std::fs::write(&existing, "old\n").unwrap();
+
+    let utf16 = SharedBuf(Arc::new(Mutex::new(Vec::new())));
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(
+            LogOutput::file(LogLevel::Trace, &fresh)
+                .unwrap()
+                .encoding(Encoding::Utf8Bom),
+        )
+        .add_output(
+            LogOutput::file(LogLevel::Trace, &existing)
+                .unwrap()
+                .encoding(Encoding::Utf8Bom),
+        )
+        .add_output(LogOutput::writer(LogLevel::Trace, utf16.clone()).encoding(Encoding::Utf16Le))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("naïve");
+    nanologger::warn!("two");
+
+    let fresh_bytes = std::fs::read(&fresh).unwrap();
+    let existing_text = std::fs::read_to_string(&existing).unwrap();
+    let _ = std::fs::remove_file(&fresh);
+    let _ = std::fs::remove_file(&existing);
+
+    assert_eq!(
+        fresh_bytes,
+        "\u{FEFF}[INFO]  naïve\n[WARN]  two\n".as_bytes()
+    );
+    assert_eq!(existing_text, "old\n[INFO]  naïve\n[WARN]  two\n");
+
+    let bytes = utf16.0.lock().unwrap().clone();
+    assert_eq!(&bytes[..2], b"\xFF\xFE");
+    let units: Vec<u16> = bytes[2..]
+        .chunks(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    assert_eq!(
+        String::from_utf16(&units).unwrap(),
+        "[INFO]  naïve\n[WARN]  two\n"
+    );
+}
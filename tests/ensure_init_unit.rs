@@ -0,0 +1,30 @@
+use nanologger::{LogLevel, LoggerBuilder};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Concurrent `ensure_init` calls run exactly one closure, and only that call
+/// reports performing the init.
+#[test]
+fn test_ensure_init_runs_one_closure() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let runs = runs.clone();
+            std::thread::spawn(move || {
+                nanologger::ensure_init(|| {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    LoggerBuilder::new().level(LogLevel::Debug)
+                })
+                .expect("ensure_init should succeed")
+            })
+        })
+        .collect();
+    let performed: Vec<bool> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert_eq!(performed.iter().filter(|&&p| p).count(), 1);
+
+    let again = nanologger::ensure_init(|| panic!("must not run after init"));
+    assert!(!again.unwrap());
+    assert!(nanologger::output_would_log(0, LogLevel::Debug));
+}
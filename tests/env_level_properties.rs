@@ -46,7 +46,10 @@ proptest! {
     #[test]
     #[serial]
     fn invalid_env_var_falls_back_to_info(s in "[a-zA-Z0-9_]{1,20}") {
-        let valid = ["error", "warn", "info", "debug", "trace"];
+        let valid = [
+            "error", "warn", "info", "debug", "trace", "e", "w", "i", "d", "t", "0", "1", "2", "3",
+            "4",
+        ];
         prop_assume!(!valid.contains(&s.to_ascii_lowercase().as_str()));
         std::env::set_var("NANOLOGGER_LEVEL", &s);
         let builder = LoggerBuilder::new();
@@ -0,0 +1,66 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ConfigError(std::io::Error);
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("failed to load config")
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// `error_chain!` logs the error and its causes as one record and respects
+/// the configured level.
+#[test]
+fn test_error_chain_macro() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Warn)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    let err = ConfigError(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "app.toml not found",
+    ));
+
+    nanologger::error_chain!(&err);
+    nanologger::error_chain!(LogLevel::Info, &err);
+
+    assert_eq!(
+        buf_reader.contents(),
+        "[ERROR] failed to load config\n  caused by: app.toml not found\n"
+    );
+}
@@ -0,0 +1,44 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::path::PathBuf;
+
+/// Returns a fresh path in the temp dir. On Unix the file name contains a
+/// non-UTF-8 byte to exercise `AsRef<Path>` handling.
+fn log_path() -> PathBuf {
+    let dir = std::env::temp_dir();
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let mut bytes = format!("nanologger-{}-", std::process::id()).into_bytes();
+        bytes.extend_from_slice(b"\xff.log");
+        dir.join(OsStr::from_bytes(&bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        dir.join(format!("nanologger-{}.log", std::process::id()))
+    }
+}
+
+/// `LogOutput::file` creates (and appends to) the file at a path that need
+/// not be valid UTF-8.
+#[test]
+fn test_file_output_non_utf8_path() {
+    let path = log_path();
+    #[cfg(unix)]
+    assert!(path.to_str().is_none(), "path should not be valid UTF-8");
+    let _ = std::fs::remove_file(&path);
+
+    std::fs::write(&path, "existing line\n").unwrap();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::file(LogLevel::Trace, &path).expect("file should open"))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Warn, "to file", "test_mod", "test.rs", 1);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(contents, "existing line\n[WARN]  to file\n");
+}
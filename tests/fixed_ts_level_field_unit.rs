@@ -0,0 +1,56 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The field width comes from the clock and the widest tag at init, so the
+/// first line is already padded to the same column as every later one.
+#[test]
+fn test_fixed_ts_level_field_is_columnar_from_the_first_line() {
+    let timed = SharedBuf::new();
+    let untimed = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .timestamps(true)
+        .clock(|| "00:00:00.000".to_string())
+        .thread_info(true)
+        .fixed_ts_level_field(true)
+        .add_output(LogOutput::writer(LogLevel::Trace, timed.clone()))
+        .add_output(LogOutput::writer(LogLevel::Trace, untimed.clone()).timestamps(false))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("ready");
+    nanologger::error!("failed");
+
+    let thread = std::thread::current();
+    let name = thread.name().unwrap();
+    assert_eq!(
+        timed.contents(),
+        format!("00:00:00.000 [INFO]  ({name}) ready\n00:00:00.000 [ERROR] ({name}) failed\n")
+    );
+    assert_eq!(
+        untimed.contents(),
+        format!("[INFO]  ({name}) ready\n[ERROR] ({name}) failed\n")
+    );
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "async")]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::future::Future;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+use std::time::Duration;
+
+/// A writer whose flush is slow, recording when it finishes.
+struct SlowFlush(Arc<AtomicBool>);
+
+impl Write for SlowFlush {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::thread::sleep(Duration::from_millis(50));
+        self.0.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Wakes the test thread by unparking it.
+struct Unpark(std::thread::Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Test that the flush runs off the polling thread and the future completes
+/// once it has finished.
+#[test]
+fn test_flush_async_completes_after_flush() {
+    let flushed = Arc::new(AtomicBool::new(false));
+    LoggerBuilder::new()
+        .add_output(LogOutput::writer(
+            LogLevel::Trace,
+            SlowFlush(flushed.clone()),
+        ))
+        .init()
+        .expect("init should succeed");
+    nanologger::info!("pending");
+
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(nanologger::flush_async());
+
+    assert!(
+        future.as_mut().poll(&mut cx).is_pending(),
+        "the first poll must not block"
+    );
+    while future.as_mut().poll(&mut cx).is_pending() {
+        std::thread::park_timeout(Duration::from_secs(5));
+    }
+    assert!(flushed.load(Ordering::SeqCst));
+}
@@ -0,0 +1,42 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Records each write and flush in order.
+#[derive(Clone)]
+struct EventLog(Arc<Mutex<Vec<String>>>);
+
+impl Write for EventLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let line = String::from_utf8_lossy(buf).trim_end().to_string();
+        self.0.lock().unwrap().push(line);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.lock().unwrap().push("flush".to_string());
+        Ok(())
+    }
+}
+
+/// Each line is followed by a flush on opted-in outputs only.
+#[test]
+fn test_flush_each_line() {
+    let flushed = EventLog(Arc::new(Mutex::new(Vec::new())));
+    let unflushed = EventLog(Arc::new(Mutex::new(Vec::new())));
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, flushed.clone()).flush_each_line(true))
+        .add_output(LogOutput::writer(LogLevel::Trace, unflushed.clone()))
+        .add_output(LogOutput::test(LogLevel::Trace).flush_each_line(true))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("one");
+    nanologger::warn!("two");
+
+    assert_eq!(
+        *flushed.0.lock().unwrap(),
+        ["[INFO]  one", "flush", "[WARN]  two", "flush"]
+    );
+    assert_eq!(*unflushed.0.lock().unwrap(), ["[INFO]  one", "[WARN]  two"]);
+}
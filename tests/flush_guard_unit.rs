@@ -0,0 +1,58 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// A writer that buffers internally and only publishes on flush.
+#[derive(Clone)]
+struct FlushOnlyBuf {
+    pending: Arc<Mutex<Vec<u8>>>,
+    flushed: Arc<Mutex<Vec<u8>>>,
+}
+
+impl FlushOnlyBuf {
+    fn new() -> Self {
+        FlushOnlyBuf {
+            pending: Arc::new(Mutex::new(Vec::new())),
+            flushed: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+    fn flushed(&self) -> String {
+        String::from_utf8_lossy(&self.flushed.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for FlushOnlyBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        self.flushed.lock().unwrap().append(&mut pending);
+        Ok(())
+    }
+}
+
+/// Dropping the guard returned by `init_with_guard` flushes every output.
+#[test]
+fn test_flush_guard_flushes_on_drop() {
+    let buf = FlushOnlyBuf::new();
+    let buf_reader = buf.clone();
+
+    let guard = LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init_with_guard()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Info, "tail message", "test_mod", "test.rs", 1);
+    assert!(
+        buf_reader.flushed().is_empty(),
+        "Nothing should be flushed before the guard is dropped"
+    );
+
+    drop(guard);
+    assert_eq!(buf_reader.flushed(), "[INFO]  tail message\n");
+
+    // A second guard cannot be obtained once the logger is initialized.
+    assert!(LoggerBuilder::new().init_with_guard().is_err());
+}
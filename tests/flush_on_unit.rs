@@ -0,0 +1,53 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Holds written bytes until `flush`, like a `BufWriter` that never fills up.
+#[derive(Clone)]
+struct Buffered {
+    pending: Arc<Mutex<Vec<u8>>>,
+    flushed: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Buffered {
+    fn flushed(&self) -> String {
+        String::from_utf8_lossy(&self.flushed.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for Buffered {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        self.flushed.lock().unwrap().append(&mut pending);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_flush_on_error_flushes_after_error_only() {
+    let out = Buffered {
+        pending: Arc::new(Mutex::new(Vec::new())),
+        flushed: Arc::new(Mutex::new(Vec::new())),
+    };
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .flush_on(LogLevel::Error)
+        .add_output(LogOutput::writer(LogLevel::Trace, out.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::warn!("still buffered");
+    nanologger::info!("also buffered");
+    assert_eq!(out.flushed(), "", "lower levels must not flush");
+
+    nanologger::error!("disk is gone");
+    let flushed = out.flushed();
+    assert!(flushed.contains("still buffered"), "got: {flushed:?}");
+    assert!(flushed.ends_with("disk is gone\n"), "got: {flushed:?}");
+
+    nanologger::debug!("after");
+    assert!(!out.flushed().contains("after"));
+}
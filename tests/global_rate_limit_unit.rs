@@ -0,0 +1,57 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that a storm is cut to the burst size and the next record after a
+/// refill is preceded by a drop summary.
+#[test]
+fn test_global_rate_limit_drops_and_summarizes() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .global_rate_limit(20, 3)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    for i in 0..10 {
+        nanologger::info!("storm {i}");
+    }
+    let output = buf_reader.contents();
+    assert_eq!(
+        output, "[INFO]  storm 0\n[INFO]  storm 1\n[INFO]  storm 2\n",
+        "only the burst should get through"
+    );
+    assert_eq!(nanologger::stats().unwrap().total(), 3);
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    nanologger::info!("calm");
+    let output = buf_reader.contents();
+    assert!(
+        output.ends_with("[WARN]  dropped 7 lines due to rate limit\n[INFO]  calm\n"),
+        "got: {output:?}"
+    );
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "hostname")]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, OutputFormat};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// With the `hostname` feature, `.hostname("")` drops the detected host from
+/// structured output too.
+#[test]
+fn test_empty_hostname_opts_out_of_detected_host() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .output_format(OutputFormat::Json)
+        .hostname("")
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("up");
+    assert_eq!(buf.contents(), "{\"level\":\"info\",\"msg\":\"up\"}\n");
+}
@@ -0,0 +1,44 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, OutputFormat};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that an explicit hostname is emitted as a `host` key in JSON.
+#[test]
+fn test_hostname_in_json() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .output_format(OutputFormat::Json)
+        .hostname("db-7")
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("up");
+    assert_eq!(
+        buf_reader.contents(),
+        "{\"level\":\"info\",\"host\":\"db-7\",\"msg\":\"up\"}\n"
+    );
+}
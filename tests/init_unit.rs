@@ -11,8 +11,12 @@ fn test_init_default_and_double_init() {
     let result2 = LoggerBuilder::new().level(LogLevel::Debug).init();
     assert!(result2.is_err(), "Second init() should return InitError");
 
-    // 3. Verify the error message.
+    // 3. Verify the reason and error message.
     let err = result2.unwrap_err();
+    assert!(
+        matches!(err, InitError::AlreadyInitialized),
+        "Second init() should fail with AlreadyInitialized, got: {err:?}"
+    );
     let msg = err.to_string();
     assert!(
         msg.contains("already initialized"),
@@ -22,18 +26,32 @@ fn test_init_default_and_double_init() {
 
 #[test]
 fn test_init_error_is_std_error() {
-    let err = InitError;
+    let err = InitError::AlreadyInitialized;
     let _: &dyn std::error::Error = &err;
 }
 
+/// I/O failures convert into `InitError::OutputIoError` and keep their source.
+#[test]
+fn test_init_error_from_io_error() {
+    use std::error::Error;
+
+    let io = std::io::Error::new(std::io::ErrorKind::NotFound, "no such dir");
+    let err = InitError::from(io);
+    assert!(matches!(err, InitError::OutputIoError(_)));
+    assert!(err.to_string().contains("no such dir"));
+    assert!(err.source().is_some());
+}
+
 #[test]
 fn test_parse_level_error_display() {
     let err: Result<LogLevel, _> = "garbage".parse();
-    let msg = err.unwrap_err().to_string();
+    let err = err.unwrap_err();
+    let msg = err.to_string();
     assert!(
         msg.contains("garbage"),
         "ParseLevelError should include the invalid input, got: {msg}"
     );
+    assert_eq!(err.input(), "garbage");
 }
 
 /// LoggerBuilder::default() produces the same result as LoggerBuilder::new().
@@ -0,0 +1,45 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, OutputKind};
+
+/// Runs this test binary's `test_name` with `__NANOLOG_KEEP_TERM_CHECK` set
+/// and returns what it wrote to stderr.
+fn child_stderr(test_name: &str) -> String {
+    let exe = std::env::current_exe().expect("current_exe");
+    let output = std::process::Command::new(exe)
+        .arg(test_name)
+        .arg("--exact")
+        .arg("--nocapture")
+        .env("__NANOLOG_KEEP_TERM_CHECK", "1")
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to spawn subprocess");
+    assert!(output.status.success(), "child failed: {output:?}");
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+/// With `keep_default_term`, adding an output still logs to stderr at the
+/// builder's level.
+#[test]
+fn test_keep_default_term_with_added_output() {
+    if std::env::var_os("__NANOLOG_KEEP_TERM_CHECK").is_some() {
+        let (output, lines) = LogOutput::buffer(LogLevel::Trace, 8);
+        LoggerBuilder::new()
+            .level(LogLevel::Debug)
+            .keep_default_term(true)
+            .add_output(output)
+            .init()
+            .expect("init should succeed");
+        nanologger::debug!("both places");
+        nanologger::trace!("filtered");
+        assert_eq!(lines.lines(), ["[DEBUG] both places"]);
+        // The kept stderr output goes last; the added output stays index 0.
+        assert_eq!(nanologger::output_bytes(0), Some(20));
+        assert_eq!(nanologger::output_bytes(1), Some(20));
+        let kinds: Vec<_> = nanologger::outputs().iter().map(|o| o.kind()).collect();
+        assert_eq!(kinds, [OutputKind::Buffer, OutputKind::Term]);
+        return;
+    }
+
+    let stderr = child_stderr("test_keep_default_term_with_added_output");
+    assert!(stderr.contains("[DEBUG] both places\n"), "got: {stderr:?}");
+    assert!(!stderr.contains("filtered"), "got: {stderr:?}");
+}
@@ -0,0 +1,55 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::cell::Cell;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Lazy closures only run for records that pass the level gate.
+#[test]
+fn test_lazy_closure_runs_only_when_enabled() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Debug)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    let calls = Cell::new(0);
+    let expensive = |label: &str| {
+        calls.set(calls.get() + 1);
+        format!("{label} computed")
+    };
+
+    nanologger::trace_lazy!(|| expensive("trace"));
+    assert_eq!(calls.get(), 0);
+
+    nanologger::debug_lazy!(|| expensive("debug"));
+    nanologger::log_lazy!(LogLevel::Warn, || 42);
+    assert_eq!(calls.get(), 1);
+
+    nanologger::set_level(LogLevel::Info);
+    nanologger::debug_lazy!(|| expensive("dropped"));
+    assert_eq!(calls.get(), 1);
+
+    assert_eq!(buf.contents(), "[DEBUG] debug computed\n[WARN]  42\n");
+}
@@ -0,0 +1,58 @@
+use nanologger::{LevelMatch, LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// With `LevelMatch::Exact`, only messages at exactly the configured level
+/// pass the global gate, and each output only sees its own exact level.
+#[test]
+fn test_exact_level_match_global_and_per_output() {
+    let debug_buf = SharedBuf::new();
+    let trace_buf = SharedBuf::new();
+    let debug_reader = debug_buf.clone();
+    let trace_reader = trace_buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Debug)
+        .level_match(LevelMatch::Exact)
+        .add_output(LogOutput::writer(LogLevel::Debug, debug_buf))
+        .add_output(LogOutput::writer(LogLevel::Trace, trace_buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Error, "error msg", "test_mod", "test.rs", 1);
+    nanologger::__log_with_context(LogLevel::Info, "info msg", "test_mod", "test.rs", 2);
+    nanologger::__log_with_context(LogLevel::Debug, "debug msg", "test_mod", "test.rs", 3);
+    nanologger::__log_with_context(LogLevel::Trace, "trace msg", "test_mod", "test.rs", 4);
+
+    let debug_out = debug_reader.contents();
+    assert_eq!(debug_out, "[DEBUG] debug msg\n");
+
+    // The Trace output only matches Trace exactly, but the global gate
+    // (exactly Debug) already rejected the Trace message.
+    let trace_out = trace_reader.contents();
+    assert!(
+        trace_out.is_empty(),
+        "Trace output should receive nothing, got: {trace_out:?}"
+    );
+}
@@ -0,0 +1,49 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `level_scope` raises the level while the guard lives and restores it on drop.
+#[test]
+fn test_level_scope_restores_previous_level() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    {
+        let _guard = nanologger::level_scope(LogLevel::Trace);
+        nanologger::__log_with_context(LogLevel::Trace, "inside", "test_mod", "test.rs", 1);
+    }
+    nanologger::__log_with_context(LogLevel::Trace, "outside", "test_mod", "test.rs", 2);
+    nanologger::__log_with_context(LogLevel::Info, "still info", "test_mod", "test.rs", 3);
+
+    assert_eq!(
+        buf_reader.contents(),
+        "[TRACE] inside\n[INFO]  still info\n"
+    );
+}
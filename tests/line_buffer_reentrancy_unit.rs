@@ -0,0 +1,32 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// An output that logs from inside its own sink still gets both lines intact,
+/// since the nested call falls back to a fresh buffer.
+#[test]
+fn test_reentrant_logging_from_output() {
+    let lines = Arc::new(Mutex::new(Vec::<String>::new()));
+    let nested = AtomicBool::new(false);
+    let sink = lines.clone();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::test_with(LogLevel::Trace, move |line| {
+            if !nested.swap(true, Ordering::SeqCst) {
+                nanologger::info!("nested");
+            }
+            sink.lock().unwrap().push(line.to_string());
+        }))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("outer");
+    nanologger::info!("{}", "x".repeat(100_000));
+    nanologger::info!("after");
+
+    let lines = lines.lock().unwrap();
+    assert_eq!(lines[0], "[INFO]  nested\n");
+    assert_eq!(lines[1], "[INFO]  outer\n");
+    assert_eq!(lines[2].len(), "[INFO]  \n".len() + 100_000);
+    assert_eq!(lines[3], "[INFO]  after\n");
+}
@@ -0,0 +1,66 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `log_at` and `log_at!` use the supplied timestamp in place of the clock and
+/// still go through the level and module filters.
+#[test]
+fn test_log_at_uses_supplied_timestamp() {
+    let buf = SharedBuf::new();
+    let untimed = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .timestamps(true)
+        .clock(|| "NOW".to_string())
+        .module_deny(vec!["log_at_unit::quiet".to_string()])
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .add_output(LogOutput::writer(LogLevel::Trace, untimed.clone()).timestamps(false))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::log_at(LogLevel::Warn, "replayed", "2024-03-01 09:15:02");
+    nanologger::log_at(LogLevel::Debug, "filtered", "2024-03-01 09:15:03");
+    let when = String::from("2024-03-01 09:15:04");
+    nanologger::log_at!(LogLevel::Info, when, "user {} signed in", "alice");
+    nanologger::log_at!(LogLevel::Error, "T", %"100% {verbatim}");
+    mod quiet {
+        pub fn log() {
+            nanologger::log_at!(nanologger::LogLevel::Error, "T", "denied");
+        }
+    }
+    quiet::log();
+    nanologger::info!("live");
+
+    assert_eq!(
+        buf.contents(),
+        "2024-03-01 09:15:02 [WARN]  replayed\n\
+         2024-03-01 09:15:04 [INFO]  user alice signed in\n\
+         T [ERROR] 100% {verbatim}\n\
+         NOW [INFO]  live\n"
+    );
+    assert_eq!(
+        untimed.contents(),
+        "[WARN]  replayed\n[INFO]  user alice signed in\n[ERROR] 100% {verbatim}\n[INFO]  live\n"
+    );
+}
@@ -18,3 +18,36 @@ fn test_logging_before_init_does_not_panic() {
         2,
     );
 }
+
+/// In debug builds, the first record dropped before init prints a one-time
+/// notice to stderr.
+///
+/// Runs itself as a subprocess (with __NANOLOG_UNINIT_CHECK=1) so the notice
+/// can be captured.
+#[cfg(debug_assertions)]
+#[test]
+fn test_uninitialized_notice_printed_once() {
+    if std::env::var("__NANOLOG_UNINIT_CHECK").is_ok() {
+        nanologger::info!("dropped one");
+        nanologger::warn!("dropped two");
+        return;
+    }
+
+    let exe = std::env::current_exe().expect("current_exe");
+    let output = std::process::Command::new(exe)
+        .arg("test_uninitialized_notice_printed_once")
+        .arg("--exact")
+        .arg("--nocapture")
+        .env("__NANOLOG_UNINIT_CHECK", "1")
+        .output()
+        .expect("failed to spawn subprocess");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr.matches("logger is not initialized").count(),
+        1,
+        "expected exactly one notice, got: {stderr:?}"
+    );
+    assert!(stderr.contains("log_before_init.rs:"), "got: {stderr:?}");
+    assert!(!stderr.contains("dropped one"), "got: {stderr:?}");
+}
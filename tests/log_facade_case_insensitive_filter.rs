@@ -0,0 +1,43 @@
+//! Tests that case-insensitive module filters match mixed-case `log` targets.
+#![cfg(feature = "log")]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedWriter(Arc<Mutex<Cursor<Vec<u8>>>>);
+
+impl std::io::Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.lock().unwrap().flush()
+    }
+}
+
+#[test]
+fn test_log_facade_case_insensitive_filter() {
+    let buf = Arc::new(Mutex::new(Cursor::new(Vec::new())));
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .module_allow(vec!["payments".to_string()])
+        .module_deny(vec!["Payments::Retry".to_string()])
+        .filter_case_insensitive(true)
+        .add_output(LogOutput::writer(
+            LogLevel::Trace,
+            SharedWriter(Arc::clone(&buf)),
+        ))
+        .init()
+        .expect("init should succeed");
+
+    log::info!(target: "Payments::Ledger", "ledger mixed case");
+    log::info!(target: "PAYMENTS", "all caps");
+    log::info!(target: "payments::retry::backoff", "denied regardless of case");
+    log::info!(target: "Shipping", "not allowed");
+
+    let data = buf.lock().unwrap();
+    let output = String::from_utf8_lossy(data.get_ref());
+    assert_eq!(output, "[INFO]  ledger mixed case\n[INFO]  all caps\n");
+}
@@ -0,0 +1,33 @@
+//! Init reports `LogFacadeClaimed` when another logger owns the `log` facade.
+#![cfg(feature = "log")]
+
+use nanologger::{InitError, LogLevel, LoggerBuilder};
+
+struct OtherLogger;
+
+impl log::Log for OtherLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        false
+    }
+    fn log(&self, _: &log::Record) {}
+    fn flush(&self) {}
+}
+
+static OTHER: OtherLogger = OtherLogger;
+
+#[test]
+fn test_init_reports_log_facade_claimed() {
+    log::set_logger(&OTHER).expect("first facade logger should register");
+
+    let err = LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .init()
+        .expect_err("init should report the claimed facade");
+    assert!(
+        matches!(err, InitError::LogFacadeClaimed),
+        "Expected LogFacadeClaimed, got: {err:?}"
+    );
+
+    // nanologger's own macros are still usable.
+    nanologger::info!("still logs via nanologger macros");
+}
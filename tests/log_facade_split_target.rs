@@ -0,0 +1,45 @@
+//! Tests that `split_target` derives `crate` and `module` keys from the facade
+//! target and from `module_path!()` for native macros.
+#![cfg(feature = "log")]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, OutputFormat};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_split_target_fields() {
+    let buf = SharedBuf(Arc::new(Mutex::new(Vec::new())));
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .output_format(OutputFormat::Json)
+        .hostname("")
+        .split_target(true)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    log::info!(target: "myapp::db::pool", "checked out");
+    nanologger::info!("native");
+
+    let out = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            r#"{"level":"info","crate":"myapp","module":"myapp::db::pool","msg":"checked out"}"#,
+            r#"{"level":"info","crate":"log_facade_split_target","module":"log_facade_split_target","msg":"native"}"#,
+        ]
+    );
+}
@@ -0,0 +1,56 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `log_fields` appends fields after the message and honors the level gate.
+#[test]
+fn test_log_fields_plain_text() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    let user = String::from("alice");
+    let keys = ["shard", "region"];
+    let values: [&dyn std::fmt::Display; 2] = [&7, &"us east"];
+    let dynamic: Vec<(&str, &dyn std::fmt::Display)> = keys.into_iter().zip(values).collect();
+
+    nanologger::log_fields(
+        LogLevel::Warn,
+        "login failed",
+        &[("user", &user), ("attempts", &3)],
+    );
+    nanologger::log_fields(LogLevel::Info, "placed", &dynamic);
+    nanologger::log_fields(LogLevel::Debug, "filtered", &[("x", &1)]);
+    nanologger::log_fields(LogLevel::Info, "no fields", &[]);
+
+    assert_eq!(
+        buf.contents(),
+        "[WARN]  login failed user=alice attempts=3\n\
+         [INFO]  placed shard=7 region=\"us east\"\n\
+         [INFO]  no fields\n"
+    );
+}
@@ -0,0 +1,52 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The header bypasses the rate limiter, message transform, and stats, so it
+/// can't be dropped or rewritten and doesn't use up the first token.
+#[test]
+fn test_log_header_bypasses_pipeline() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .log_header(true)
+        .global_rate_limit(0, 0)
+        .message_transform(|msg| msg.to_uppercase().into())
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    assert_eq!(nanologger::stats().unwrap().total(), 0);
+    nanologger::info!("first");
+
+    let contents = buf.contents();
+    let lines: Vec<_> = contents.lines().collect();
+    assert_eq!(lines.len(), 2, "got: {contents:?}");
+    assert!(
+        lines[0].starts_with("[INFO]  nanologger ") && lines[0].contains(" level=trace "),
+        "header should be untransformed: {contents:?}"
+    );
+    assert_eq!(lines[1], "[INFO]  FIRST");
+    assert_eq!(nanologger::stats().unwrap().total(), 1);
+}
@@ -0,0 +1,55 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `log_header(true)` writes one Info record describing the configuration,
+/// skipped by outputs whose filter excludes Info.
+#[test]
+fn test_log_header_emitted_on_init() {
+    let info = SharedBuf::new();
+    let errors = SharedBuf::new();
+    let info_reader = info.clone();
+    let errors_reader = errors.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .timestamps(true)
+        .clock(|| "00:00:00.000".to_string())
+        .log_header(true)
+        .add_output(LogOutput::writer(LogLevel::Info, info))
+        .add_output(LogOutput::writer(LogLevel::Error, errors))
+        .init()
+        .expect("init should succeed");
+
+    let expected = format!(
+        "00:00:00.000 [INFO]  nanologger {} level=trace level_match=AtLeast timestamps=true \
+         source_location=false thread_info=false sequence_numbers=false format=plain\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    assert_eq!(info_reader.contents(), expected);
+    assert!(
+        errors_reader.contents().is_empty(),
+        "Error-only output should skip the header"
+    );
+}
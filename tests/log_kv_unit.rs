@@ -0,0 +1,69 @@
+use nanologger::{FieldValue, LogLevel, LogOutput, LoggerBuilder, OutputFormat};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `log_kv!` keeps numbers and booleans typed in JSON and honors the module
+/// filter and level gate.
+#[test]
+fn test_log_kv_typed_json() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .output_format(OutputFormat::Json)
+        .hostname("")
+        .module_deny(vec!["log_kv_unit::quiet".to_string()])
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    let path = String::from("/health");
+    let elapsed = 0.25_f32;
+    nanologger::log_kv!(LogLevel::Info, "request done";
+        path = &path, status = 200u16, bytes = 512usize, delta = -3, elapsed = elapsed, cached = true,
+    );
+    nanologger::log_kv!(LogLevel::Warn, format!("retry {}", 2); attempt = 2);
+    nanologger::log_kv!(LogLevel::Debug, "filtered"; x = 1);
+    mod quiet {
+        pub fn log() {
+            nanologger::log_kv!(nanologger::LogLevel::Error, "denied"; x = 1);
+        }
+    }
+    quiet::log();
+
+    assert_eq!(
+        buf.contents(),
+        "{\"level\":\"info\",\"msg\":\"request done\",\"path\":\"/health\",\"status\":200,\"bytes\":512,\"delta\":-3,\"elapsed\":0.25,\"cached\":true}\n\
+         {\"level\":\"warn\",\"msg\":\"retry 2\",\"attempt\":2}\n"
+    );
+}
+
+#[test]
+fn test_field_value_conversions() {
+    assert_eq!(FieldValue::from(-1i8), FieldValue::I64(-1));
+    assert_eq!(FieldValue::from(7u32), FieldValue::U64(7));
+    assert_eq!(FieldValue::from(1.5f32), FieldValue::F64(1.5));
+    assert_eq!(FieldValue::from(false), FieldValue::Bool(false));
+    assert_eq!(FieldValue::from("a"), FieldValue::Str("a"));
+    assert_eq!(FieldValue::F64(2.0).to_string(), "2");
+}
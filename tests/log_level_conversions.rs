@@ -0,0 +1,36 @@
+#![cfg(feature = "log")]
+
+use nanologger::LogLevel;
+
+#[test]
+fn test_log_level_round_trips_through_log_level() {
+    for level in LogLevel::iter() {
+        let converted: log::Level = level.into();
+        assert_eq!(LogLevel::from(converted), level);
+        assert!(converted.as_str().eq_ignore_ascii_case(&level.to_string()));
+    }
+}
+
+#[test]
+fn test_log_level_into_level_filter() {
+    assert_eq!(
+        log::LevelFilter::from(LogLevel::Error),
+        log::LevelFilter::Error
+    );
+    assert_eq!(
+        log::LevelFilter::from(LogLevel::Warn),
+        log::LevelFilter::Warn
+    );
+    assert_eq!(
+        log::LevelFilter::from(LogLevel::Info),
+        log::LevelFilter::Info
+    );
+    assert_eq!(
+        log::LevelFilter::from(LogLevel::Debug),
+        log::LevelFilter::Debug
+    );
+    assert_eq!(
+        log::LevelFilter::from(LogLevel::Trace),
+        log::LevelFilter::Trace
+    );
+}
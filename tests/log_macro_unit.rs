@@ -0,0 +1,50 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `log!` logs at a level computed at runtime and honors the level filter.
+#[test]
+fn test_log_macro_dynamic_level() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    for failures in 1..=3 {
+        let level = if failures >= 3 {
+            LogLevel::Error
+        } else {
+            LogLevel::Warn
+        };
+        nanologger::log!(level, "{failures} failures");
+    }
+    nanologger::log!(LogLevel::Debug, "filtered out");
+
+    assert_eq!(
+        buf.contents(),
+        "[WARN]  1 failures\n[WARN]  2 failures\n[ERROR] 3 failures\n"
+    );
+}
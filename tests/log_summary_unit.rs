@@ -0,0 +1,50 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `shutdown` writes the summary past the level filters when enabled, and
+/// the summary itself isn't counted.
+#[test]
+fn test_summary_on_shutdown() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Warn)
+        .summary_on_shutdown(true)
+        .add_output(LogOutput::writer(LogLevel::Error, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::error!("disk full");
+    nanologger::warn!("slow");
+    nanologger::warn!("slower");
+    nanologger::info!("filtered out");
+    nanologger::shutdown();
+    nanologger::log_summary();
+
+    let summary = "[ERROR] nanologger: 1 error, 2 warnings during run (3 records)\n";
+    assert_eq!(
+        buf.contents(),
+        format!("[ERROR] disk full\n{summary}{summary}")
+    );
+}
@@ -0,0 +1,54 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, MultilinePolicy};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Tables go through the usual level filter and multiline policy.
+#[test]
+fn test_log_table_respects_level_and_multiline() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .multiline(MultilinePolicy::Split)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::log_table(LogLevel::Debug, &["hidden"], [["row"]]);
+    nanologger::log_table(
+        LogLevel::Info,
+        &["id", "name", "score"],
+        [
+            [1.to_string(), "alice".into(), 9.to_string()],
+            [2.to_string(), "bob".into(), 12.to_string()],
+        ],
+    );
+
+    assert_eq!(
+        buf.contents(),
+        "[INFO]  id | name  | score\n\
+         [INFO]  ---+-------+------\n\
+         [INFO]  1  | alice | 9\n\
+         [INFO]  2  | bob   | 12\n"
+    );
+}
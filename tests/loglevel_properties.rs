@@ -37,10 +37,32 @@ proptest! {
         prop_assert_eq!(level, parsed);
     }
 
+    /// Lowercase and uppercase names both parse back to the level, and the
+    /// lowercase name matches `Display`.
+    #[test]
+    fn test_loglevel_str_variants_roundtrip(level in arb_log_level()) {
+        prop_assert_eq!(level.as_str_lower(), level.to_string());
+        prop_assert_eq!(LogLevel::from_str(level.as_str_lower()).unwrap(), level);
+        prop_assert_eq!(LogLevel::from_str(level.as_str_upper()).unwrap(), level);
+        prop_assert_eq!(level.as_str_short().len(), 3);
+    }
+
+    /// The first letter (either case) and the numeric value parse to the level.
+    #[test]
+    fn test_loglevel_short_forms_parse(level in arb_log_level()) {
+        let letter = &level.as_str_lower()[..1];
+        prop_assert_eq!(LogLevel::from_str(letter).unwrap(), level);
+        prop_assert_eq!(LogLevel::from_str(&letter.to_ascii_uppercase()).unwrap(), level);
+        prop_assert_eq!(LogLevel::from_str(&level.as_u8().to_string()).unwrap(), level);
+    }
+
     /// Non-level strings are rejected by from_str.
     #[test]
     fn test_invalid_level_rejected(s in "[a-zA-Z0-9_]{1,20}") {
-        let valid = ["error", "warn", "info", "debug", "trace"];
+        let valid = [
+            "error", "warn", "info", "debug", "trace", "e", "w", "i", "d", "t", "0", "1", "2", "3",
+            "4",
+        ];
         if !valid.contains(&s.to_ascii_lowercase().as_str()) {
             prop_assert!(LogLevel::from_str(&s).is_err());
         }
@@ -54,12 +76,50 @@ proptest! {
         prop_assert_eq!(LogLevel::from_u8(val), Some(level));
     }
 
+    /// from_verbosity shifts by the net count and clamps to Error..=Trace.
+    #[test]
+    fn test_from_verbosity_clamped_shift(
+        base in arb_log_level(),
+        verbose in -10i32..10,
+        quiet in -10i32..10,
+    ) {
+        let expected = (base.as_u8() as i32 + verbose - quiet).clamp(0, 4) as u8;
+        prop_assert_eq!(
+            LogLevel::from_verbosity(base, verbose, quiet),
+            LogLevel::from_u8(expected).unwrap()
+        );
+    }
+
+    /// from_verbosity_str counts `v` and `q` in any order, like from_verbosity.
+    #[test]
+    fn test_from_verbosity_str_counts(base in arb_log_level(), flags in "[vq]{0,12}") {
+        let verbose = flags.matches('v').count() as i32;
+        let quiet = flags.matches('q').count() as i32;
+        prop_assert_eq!(
+            LogLevel::from_verbosity_str(base, &flags).unwrap(),
+            LogLevel::from_verbosity(base, verbose, quiet)
+        );
+    }
+
     #[test]
     fn test_invalid_u8_returns_none(val in 5u8..=u8::MAX) {
         prop_assert_eq!(LogLevel::from_u8(val), None);
     }
 }
 
+/// LogLevel::all() and LogLevel::iter() list every level from highest to
+/// lowest severity.
+#[test]
+fn test_loglevel_all_and_iter_order() {
+    let all = LogLevel::all();
+    assert_eq!(all, LogLevel::ALL);
+    assert!(all.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(LogLevel::iter().collect::<Vec<_>>(), all.to_vec());
+    for val in 0..=4u8 {
+        assert_eq!(LogLevel::from_u8(val), Some(all[val as usize]));
+    }
+}
+
 /// ParseLevelError Display includes the invalid input string.
 #[test]
 fn test_parse_level_error_display() {
@@ -70,3 +130,44 @@ fn test_parse_level_error_display() {
         "ParseLevelError should include the invalid input, got: {msg}"
     );
 }
+
+#[test]
+fn test_loglevel_short_names() {
+    let short: Vec<&str> = LogLevel::iter().map(LogLevel::as_str_short).collect();
+    assert_eq!(short, ["ERR", "WRN", "INF", "DBG", "TRC"]);
+}
+
+#[test]
+fn test_loglevel_syslog_severity() {
+    let severities: Vec<u8> = LogLevel::iter().map(LogLevel::syslog_severity).collect();
+    assert_eq!(severities, [3, 4, 6, 7, 7]);
+}
+
+/// from_verbosity_str: empty, all-`v`, all-`q`, mixed, and invalid input.
+#[test]
+fn test_from_verbosity_str_cases() {
+    let info = LogLevel::Info;
+    assert_eq!(LogLevel::from_verbosity_str(info, "").unwrap(), info);
+    assert_eq!(
+        LogLevel::from_verbosity_str(info, "v").unwrap(),
+        LogLevel::Debug
+    );
+    assert_eq!(
+        LogLevel::from_verbosity_str(info, "vvvvv").unwrap(),
+        LogLevel::Trace
+    );
+    assert_eq!(
+        LogLevel::from_verbosity_str(info, "qq").unwrap(),
+        LogLevel::Error
+    );
+    assert_eq!(
+        LogLevel::from_verbosity_str(info, "vqv").unwrap(),
+        LogLevel::Debug
+    );
+    assert_eq!(
+        LogLevel::from_verbosity_str(info, "qvq").unwrap(),
+        LogLevel::Warn
+    );
+    let err = LogLevel::from_verbosity_str(info, "-vv").unwrap_err();
+    assert_eq!(err.input(), "-vv");
+}
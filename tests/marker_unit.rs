@@ -0,0 +1,64 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// A writer that buffers internally and only publishes on flush.
+#[derive(Clone)]
+struct FlushOnlyBuf {
+    pending: Arc<Mutex<Vec<u8>>>,
+    flushed: Arc<Mutex<Vec<u8>>>,
+}
+
+impl FlushOnlyBuf {
+    fn new() -> Self {
+        FlushOnlyBuf {
+            pending: Arc::new(Mutex::new(Vec::new())),
+            flushed: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+    fn flushed(&self) -> String {
+        String::from_utf8_lossy(&self.flushed.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for FlushOnlyBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        self.flushed.lock().unwrap().append(&mut pending);
+        Ok(())
+    }
+}
+
+/// `marker()` writes a separator at the configured level, flushes, and obeys
+/// the global and per-output level filters.
+#[test]
+fn test_marker_writes_separator_and_flushes() {
+    // No-op before init.
+    nanologger::marker("ignored");
+
+    let all = FlushOnlyBuf::new();
+    let errors_only = FlushOnlyBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .marker_level(LogLevel::Warn)
+        .add_output(LogOutput::writer(LogLevel::Trace, all.clone()))
+        .add_output(LogOutput::writer(LogLevel::Error, errors_only.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::marker("batch 1 done");
+    let out = all.flushed();
+    assert!(out.contains("---- batch 1 done ----"), "got: {out:?}");
+    assert!(
+        out.contains("[WARN]"),
+        "marker should use marker_level: {out:?}"
+    );
+    assert!(errors_only.flushed().is_empty());
+
+    nanologger::set_level(LogLevel::Error);
+    nanologger::marker("hidden");
+    assert!(!all.flushed().contains("hidden"));
+}
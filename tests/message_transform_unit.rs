@@ -0,0 +1,60 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+thread_local! {
+    static TRACE_ID: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Test that the transform rewrites messages after filtering and can read
+/// thread-local state of the logging thread.
+#[test]
+fn test_message_transform_rewrites_messages() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .message_transform(|msg| match TRACE_ID.with(Cell::get) {
+            0 => Cow::Borrowed(msg),
+            id => Cow::Owned(format!(
+                "[trace={id}] {}",
+                msg.replace("db.internal", "db.example.com")
+            )),
+        })
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("untouched");
+    TRACE_ID.with(|id| id.set(42));
+    nanologger::warn!("lost connection to db.internal");
+    nanologger::debug!("filtered before the transform runs");
+    assert_eq!(
+        buf_reader.contents(),
+        "[INFO]  untouched\n[WARN]  [trace=42] lost connection to db.example.com\n"
+    );
+}
@@ -0,0 +1,27 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// A blocked reporter callback doesn't hang `shutdown()`; the thread is
+/// detached after the stop timeout.
+#[test]
+fn test_shutdown_detaches_blocked_reporter() {
+    let (entered_tx, entered) = mpsc::channel();
+    LoggerBuilder::new()
+        .add_output(LogOutput::writer(LogLevel::Trace, std::io::sink()))
+        .metrics_reporter(Duration::from_millis(10), move |_| {
+            let _ = entered_tx.send(());
+            std::thread::sleep(Duration::from_secs(30));
+        })
+        .init()
+        .expect("init should succeed");
+
+    entered
+        .recv_timeout(Duration::from_secs(5))
+        .expect("callback should start");
+    let started = Instant::now();
+    nanologger::shutdown();
+    let waited = started.elapsed();
+    assert!(waited >= Duration::from_millis(900), "waited {waited:?}");
+    assert!(waited < Duration::from_secs(10), "waited {waited:?}");
+}
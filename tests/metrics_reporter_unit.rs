@@ -0,0 +1,49 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// The reporter pushes per-level counts periodically and stops on `shutdown()`.
+#[test]
+fn test_metrics_reporter_pushes_stats_until_shutdown() {
+    assert!(nanologger::stats().is_none());
+
+    let (tx, rx) = mpsc::channel();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Error, std::io::sink()))
+        .metrics_reporter(Duration::from_millis(10), move |stats| {
+            let _ = tx.send(*stats);
+        })
+        .init()
+        .expect("init should succeed");
+
+    nanologger::error!("one");
+    nanologger::warn!("two");
+    nanologger::info!("three");
+    nanologger::debug!("below the global level, not counted");
+
+    let stats = nanologger::stats().unwrap();
+    assert_eq!(stats.get(LogLevel::Error), 1);
+    assert_eq!(stats.get(LogLevel::Warn), 1);
+    assert_eq!(stats.get(LogLevel::Info), 1);
+    assert_eq!(stats.get(LogLevel::Debug), 0);
+    assert_eq!(stats.total(), 3);
+
+    let reported = loop {
+        let report = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("reporter should report");
+        if report.total() == 3 {
+            break report;
+        }
+    };
+    assert_eq!(reported, stats);
+
+    nanologger::shutdown();
+    // The thread has exited, so its sender is dropped once pending reports drain.
+    while rx.recv_timeout(Duration::from_secs(5)).is_ok() {}
+    assert!(matches!(
+        rx.try_recv(),
+        Err(mpsc::TryRecvError::Disconnected)
+    ));
+}
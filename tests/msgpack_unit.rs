@@ -0,0 +1,133 @@
+#![cfg(feature = "msgpack")]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Map(Vec<(String, Value)>),
+}
+
+fn read_string(rd: &mut &[u8]) -> String {
+    let len = rmp::decode::read_str_len(rd).unwrap() as usize;
+    let (s, rest) = rd.split_at(len);
+    *rd = rest;
+    String::from_utf8(s.to_vec()).unwrap()
+}
+
+fn read_value(rd: &mut &[u8]) -> Value {
+    use rmp::Marker;
+    match Marker::from_u8(rd[0]) {
+        Marker::F64 => Value::Float(rmp::decode::read_f64(rd).unwrap()),
+        Marker::True | Marker::False => Value::Bool(rmp::decode::read_bool(rd).unwrap()),
+        Marker::FixStr(_) | Marker::Str8 | Marker::Str16 | Marker::Str32 => {
+            Value::Str(read_string(rd))
+        }
+        Marker::FixMap(_) | Marker::Map16 | Marker::Map32 => {
+            let len = rmp::decode::read_map_len(rd).unwrap();
+            Value::Map(
+                (0..len)
+                    .map(|_| (read_string(rd), read_value(rd)))
+                    .collect(),
+            )
+        }
+        _ => Value::Int(rmp::decode::read_int(rd).unwrap()),
+    }
+}
+
+/// Decodes every length-prefixed frame in `bytes` into `(key, value)` lists.
+fn decode_frames(mut bytes: &[u8]) -> Vec<Vec<(String, Value)>> {
+    let mut frames = Vec::new();
+    while !bytes.is_empty() {
+        let len = u32::from_be_bytes(bytes[..4].try_into().unwrap()) as usize;
+        let mut frame = &bytes[4..4 + len];
+        bytes = &bytes[4 + len..];
+        let fields = rmp::decode::read_map_len(&mut frame).unwrap();
+        let mut map = Vec::new();
+        for _ in 0..fields {
+            let key = read_string(&mut frame);
+            let value = read_value(&mut frame);
+            map.push((key, value));
+        }
+        assert!(frame.is_empty(), "frame fully consumed");
+        frames.push(map);
+    }
+    frames
+}
+
+/// Records written by a msgpack output round-trip through `rmp`.
+#[test]
+fn test_msgpack_round_trip() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .sequence_numbers(true)
+        .hostname("")
+        .add_output(LogOutput::msgpack(LogLevel::Debug, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    let long = "x".repeat(300);
+    nanologger::warn!("disk at {}%", 91);
+    nanologger::trace!("filtered by the output level");
+    nanologger::info!(%long);
+    let line = line!() - 1;
+    nanologger::log_kv!(LogLevel::Info, "typed"; n = -40, big = i64::MIN, r = 1.5, ok = false, s = "x");
+
+    let bytes = buf.0.lock().unwrap().clone();
+    assert_eq!(bytes.len() as u64, nanologger::output_bytes(0).unwrap());
+    let frames = decode_frames(&bytes);
+    assert_eq!(frames.len(), 3);
+    assert_eq!(
+        frames[1],
+        vec![
+            ("seq".to_string(), Value::Int(3)),
+            ("level".to_string(), Value::Int(2)),
+            ("module".to_string(), Value::Str("msgpack_unit".to_string())),
+            ("file".to_string(), Value::Str(file!().to_string())),
+            ("line".to_string(), Value::Int(line.into())),
+            ("msg".to_string(), Value::Str(long)),
+        ]
+    );
+    assert_eq!(
+        frames[2].last().unwrap(),
+        &(
+            "fields".to_string(),
+            Value::Map(vec![
+                ("n".to_string(), Value::Int(-40)),
+                ("big".to_string(), Value::Int(i64::MIN)),
+                ("r".to_string(), Value::Float(1.5)),
+                ("ok".to_string(), Value::Bool(false)),
+                ("s".to_string(), Value::Str("x".to_string())),
+            ])
+        )
+    );
+    assert_eq!(frames[0][1], ("level".to_string(), Value::Int(1)));
+    assert_eq!(
+        frames[0][5],
+        ("msg".to_string(), Value::Str("disk at 91%".to_string()))
+    );
+}
@@ -0,0 +1,70 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A writer that always fails on write.
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::BrokenPipe,
+            "simulated failure",
+        ))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Each output counts the bytes it wrote successfully, independently.
+#[test]
+fn test_output_bytes_per_output() {
+    assert_eq!(nanologger::output_bytes(0), None);
+
+    let all = SharedBuf::new();
+    let warn = SharedBuf::new();
+    let all_reader = all.clone();
+    let warn_reader = warn.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::writer(LogLevel::Trace, all))
+        .add_output(LogOutput::writer(LogLevel::Warn, warn))
+        .add_output(LogOutput::writer(LogLevel::Trace, FailingWriter))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Info, "info", "test_mod", "test.rs", 1);
+    nanologger::__log_with_context(LogLevel::Error, "error", "test_mod", "test.rs", 2);
+
+    assert_eq!(nanologger::output_bytes(0), Some(all_reader.len() as u64));
+    assert_eq!(nanologger::output_bytes(1), Some(warn_reader.len() as u64));
+    assert_eq!(
+        nanologger::output_bytes(1),
+        Some("[ERROR] error\n".len() as u64)
+    );
+    assert_eq!(nanologger::output_bytes(2), Some(0));
+    assert_eq!(nanologger::output_bytes(3), None);
+}
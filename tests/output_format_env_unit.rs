@@ -0,0 +1,41 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `NANOLOG_FORMAT` selects the format in `LoggerBuilder::new`, case-insensitively.
+#[test]
+fn test_nanolog_format_env() {
+    std::env::set_var("NANOLOG_FORMAT", "JSON");
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .hostname("")
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+    std::env::remove_var("NANOLOG_FORMAT");
+
+    nanologger::info!("hello");
+    assert_eq!(buf.contents(), "{\"level\":\"info\",\"msg\":\"hello\"}\n");
+}
@@ -0,0 +1,25 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+
+/// `output_would_log` combines the global gate with each output's filter.
+#[test]
+fn test_output_would_log() {
+    assert!(!nanologger::output_would_log(0, LogLevel::Error));
+
+    LoggerBuilder::new()
+        .level(LogLevel::Debug)
+        .add_output(LogOutput::writer(LogLevel::Warn, std::io::sink()))
+        .add_output(LogOutput::writer(LogLevel::Trace, std::io::sink()))
+        .init()
+        .expect("init should succeed");
+
+    assert!(nanologger::output_would_log(0, LogLevel::Warn));
+    assert!(!nanologger::output_would_log(0, LogLevel::Info));
+    assert!(nanologger::output_would_log(1, LogLevel::Debug));
+    // Output 1 accepts Trace, but the global gate does not.
+    assert!(!nanologger::output_would_log(1, LogLevel::Trace));
+    assert!(!nanologger::output_would_log(2, LogLevel::Error));
+
+    nanologger::set_thread_level(Some(LogLevel::Trace));
+    assert!(nanologger::output_would_log(1, LogLevel::Trace));
+    nanologger::set_thread_level(None);
+}
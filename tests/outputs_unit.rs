@@ -0,0 +1,31 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, OutputKind};
+
+/// `outputs()` is empty before init, then lists each output's kind and level
+/// in `add_output` order.
+#[test]
+fn test_outputs_lists_kinds_and_levels() {
+    assert!(nanologger::outputs().is_empty());
+
+    let (buffer, _handle) = LogOutput::buffer(LogLevel::Debug, 8);
+    LoggerBuilder::new()
+        .add_output(LogOutput::term(LogLevel::Warn))
+        .add_output(LogOutput::writer(LogLevel::Error, std::io::sink()))
+        .add_output(LogOutput::test(LogLevel::Trace))
+        .add_output(buffer)
+        .init()
+        .expect("init should succeed");
+
+    let listed: Vec<_> = nanologger::outputs()
+        .iter()
+        .map(|info| (info.kind(), info.level()))
+        .collect();
+    assert_eq!(
+        listed,
+        [
+            (OutputKind::Term, LogLevel::Warn),
+            (OutputKind::Writer, LogLevel::Error),
+            (OutputKind::Test, LogLevel::Trace),
+            (OutputKind::Buffer, LogLevel::Debug),
+        ]
+    );
+}
@@ -0,0 +1,63 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Per-output timestamp and thread-info overrides take precedence over the
+/// logger-wide settings, and outputs without overrides inherit them.
+#[test]
+fn test_per_output_timestamps_and_thread_info() {
+    let compact = SharedBuf::new();
+    let full = SharedBuf::new();
+    let inherited = SharedBuf::new();
+    let compact_reader = compact.clone();
+    let full_reader = full.clone();
+    let inherited_reader = inherited.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .timestamps(true)
+        .clock(|| "12:00:00.000".to_string())
+        .add_output(LogOutput::writer(LogLevel::Trace, compact).timestamps(false))
+        .add_output(LogOutput::writer(LogLevel::Trace, full).thread_info(true))
+        .add_output(LogOutput::writer(LogLevel::Trace, inherited))
+        .init()
+        .expect("init should succeed");
+
+    std::thread::Builder::new()
+        .name("meta-worker".into())
+        .spawn(|| {
+            nanologger::__log_with_context(LogLevel::Info, "hello", "test_mod", "test.rs", 1);
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(compact_reader.contents(), "[INFO]  hello\n");
+
+    assert_eq!(
+        full_reader.contents(),
+        "12:00:00.000 (meta-worker) [INFO]  hello\n"
+    );
+    assert_eq!(inherited_reader.contents(), "12:00:00.000 [INFO]  hello\n");
+}
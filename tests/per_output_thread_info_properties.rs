@@ -27,14 +27,6 @@ impl Write for SharedBuf {
     }
 }
 
-const ALL_LEVELS: [LogLevel; 5] = [
-    LogLevel::Error,
-    LogLevel::Warn,
-    LogLevel::Info,
-    LogLevel::Debug,
-    LogLevel::Trace,
-];
-
 fn arb_log_level() -> impl Strategy<Value = LogLevel> {
     prop_oneof![
         Just(LogLevel::Error),
@@ -51,9 +43,8 @@ fn arb_log_level() -> impl Strategy<Value = LogLevel> {
 #[test]
 fn test_per_output_level_filtering_with_thread_info() {
     // Create one Writer per LogLevel filter with thread_info enabled.
-    let bufs: Vec<(LogLevel, SharedBuf)> = ALL_LEVELS
-        .iter()
-        .map(|&lvl| (lvl, SharedBuf::new()))
+    let bufs: Vec<(LogLevel, SharedBuf)> = LogLevel::iter()
+        .map(|lvl| (lvl, SharedBuf::new()))
         .collect();
 
     let mut builder = LoggerBuilder::new()
@@ -0,0 +1,42 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, Preset};
+use std::sync::{Arc, Mutex};
+
+/// `Preset::Development` defaults to Trace but defers to `NANOLOGGER_LEVEL`.
+#[test]
+fn test_development_preset_level() {
+    std::env::remove_var("NANOLOGGER_LEVEL");
+    assert_eq!(
+        LoggerBuilder::preset(Preset::Development).get_level(),
+        LogLevel::Trace
+    );
+}
+
+/// `Preset::Production` writes JSON at Info with a UTC timestamp, and chained
+/// calls still apply on top of it.
+#[test]
+fn test_production_preset_json() {
+    let lines = Arc::new(Mutex::new(Vec::<String>::new()));
+    let sink = lines.clone();
+    let builder = LoggerBuilder::preset(Preset::Production);
+    assert_eq!(builder.get_level(), LogLevel::Info);
+    builder
+        .app_name("svc")
+        .hostname("")
+        .add_output(LogOutput::test_with(LogLevel::Trace, move |line| {
+            sink.lock().unwrap().push(line.to_string());
+        }))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("ready");
+    nanologger::debug!("filtered");
+
+    let lines = lines.lock().unwrap();
+    assert_eq!(lines.len(), 1);
+    let line = &lines[0];
+    assert!(line.starts_with("{\"ts\":\""), "got: {line:?}");
+    assert!(
+        line.ends_with("\"level\":\"info\",\"app\":\"svc\",\"msg\":\"ready\"}\n"),
+        "got: {line:?}"
+    );
+}
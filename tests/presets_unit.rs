@@ -0,0 +1,34 @@
+use nanologger::{LogLevel, LoggerBuilder};
+
+/// `dev_default` defaults to Debug but defers to `NANOLOGGER_LEVEL`.
+#[test]
+fn test_dev_default_level() {
+    std::env::remove_var("NANOLOGGER_LEVEL");
+    assert_eq!(LoggerBuilder::dev_default().get_level(), LogLevel::Debug);
+
+    std::env::set_var("NANOLOGGER_LEVEL", "trace");
+    assert_eq!(LoggerBuilder::dev_default().get_level(), LogLevel::Trace);
+    std::env::remove_var("NANOLOGGER_LEVEL");
+}
+
+/// `service_default` writes timestamped records to the file.
+#[test]
+fn test_service_default_writes_to_file() {
+    let path = std::env::temp_dir().join(format!("nanologger-svc-{}.log", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    LoggerBuilder::service_default(&path)
+        .expect("file should open")
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("service started");
+    nanologger::shutdown();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let line = contents.lines().next().expect("one line");
+    assert!(line.ends_with("[INFO]  service started"), "got: {line:?}");
+    // Timestamp prefix like `12:34:56.789`.
+    assert_eq!(line.as_bytes()[2], b':', "got: {line:?}");
+}
@@ -0,0 +1,42 @@
+#![cfg(unix)]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::io::{AsRawFd, OwnedFd};
+
+/// Test that records reach a borrowed fd without closing it, and an owned fd.
+#[test]
+fn test_raw_fd_and_owned_fd_outputs() {
+    let path = std::env::temp_dir().join(format!("nanologger_raw_fd_{}.log", std::process::id()));
+    let mut borrowed = std::fs::File::options()
+        .create(true)
+        .truncate(true)
+        .read(true)
+        .write(true)
+        .open(&path)
+        .unwrap();
+    let owned_path = path.with_extension("owned.log");
+    let owned = std::fs::File::create(&owned_path).unwrap();
+
+    LoggerBuilder::new()
+        .add_output(LogOutput::raw_fd(LogLevel::Trace, borrowed.as_raw_fd()))
+        .add_output(LogOutput::owned_fd(LogLevel::Warn, OwnedFd::from(owned)))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("to fd");
+    nanologger::warn!("to both");
+
+    // The borrowed fd is still open and usable through the original handle.
+    let mut contents = String::new();
+    borrowed.seek(SeekFrom::Start(0)).unwrap();
+    borrowed.read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "[INFO]  to fd\n[WARN]  to both\n");
+    assert_eq!(
+        std::fs::read_to_string(&owned_path).unwrap(),
+        "[WARN]  to both\n"
+    );
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&owned_path);
+}
@@ -0,0 +1,63 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::{ErrorKind, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Accepts at most 4 bytes per write and fails with `WouldBlock` whenever
+/// `stalls` is non-zero, counting it down. `stalls = u32::MAX` never recovers.
+#[derive(Clone)]
+struct FlakyWriter {
+    out: Arc<Mutex<Vec<u8>>>,
+    stalls: Arc<Mutex<u32>>,
+}
+
+impl Write for FlakyWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut stalls = self.stalls.lock().unwrap();
+        if *stalls > 0 {
+            if *stalls != u32::MAX {
+                *stalls -= 1;
+            }
+            return Err(ErrorKind::WouldBlock.into());
+        }
+        // Stall again before the rest of the line.
+        *stalls = 1;
+        let n = buf.len().min(4);
+        self.out.lock().unwrap().extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Transient errors are retried without repeating bytes; errors that outlast
+/// the retries drop the line.
+#[test]
+fn test_retry_transient_errors() {
+    let writer = FlakyWriter {
+        out: Arc::new(Mutex::new(Vec::new())),
+        stalls: Arc::new(Mutex::new(2)),
+    };
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(
+            LogOutput::writer(LogLevel::Trace, writer.clone())
+                .retry_transient(3, Duration::from_micros(10)),
+        )
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("retried");
+    assert_eq!(
+        String::from_utf8_lossy(&writer.out.lock().unwrap()),
+        "[INFO]  retried\n"
+    );
+
+    *writer.stalls.lock().unwrap() = u32::MAX;
+    nanologger::info!("dropped");
+    assert_eq!(
+        String::from_utf8_lossy(&writer.out.lock().unwrap()),
+        "[INFO]  retried\n"
+    );
+}
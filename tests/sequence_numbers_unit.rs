@@ -0,0 +1,48 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sequence numbers start at 1 and only advance for messages that pass the
+/// global level gate.
+#[test]
+fn test_sequence_numbers_count_emitted_messages() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .sequence_numbers(true)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Info, "first", "test_mod", "test.rs", 1);
+    nanologger::__log_with_context(LogLevel::Debug, "dropped", "test_mod", "test.rs", 2);
+    nanologger::__log_with_context(LogLevel::Warn, "second", "test_mod", "test.rs", 3);
+
+    assert_eq!(
+        buf_reader.contents(),
+        "#000001 [INFO]  first\n#000002 [WARN]  second\n"
+    );
+}
@@ -0,0 +1,70 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A level change made on one thread is observed by a logging thread within
+/// a bounded time, and data written before `set_level` is visible to it.
+#[test]
+fn test_set_level_visible_across_threads() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    let config_ready = Arc::new(AtomicBool::new(false));
+
+    let reader_flag = Arc::clone(&config_ready);
+    let reader = std::thread::spawn(move || {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            nanologger::__log_with_context(LogLevel::Debug, "probe", "test_mod", "test.rs", 1);
+            if buf_reader.contents().contains("probe") {
+                // The level change happened-after the flag was set.
+                return reader_flag.load(Ordering::Relaxed);
+            }
+            std::thread::yield_now();
+        }
+        panic!("level change was not observed within the deadline");
+    });
+
+    let setter_flag = Arc::clone(&config_ready);
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        setter_flag.store(true, Ordering::Relaxed);
+        nanologger::set_level(LogLevel::Debug);
+    })
+    .join()
+    .unwrap();
+
+    assert!(
+        reader.join().unwrap(),
+        "Writes made before set_level should be visible once the new level is"
+    );
+}
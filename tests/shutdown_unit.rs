@@ -0,0 +1,58 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// A writer that buffers internally and only publishes on flush.
+#[derive(Clone)]
+struct FlushOnlyBuf {
+    pending: Arc<Mutex<Vec<u8>>>,
+    flushed: Arc<Mutex<Vec<u8>>>,
+}
+
+impl FlushOnlyBuf {
+    fn new() -> Self {
+        FlushOnlyBuf {
+            pending: Arc::new(Mutex::new(Vec::new())),
+            flushed: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+    fn flushed(&self) -> String {
+        String::from_utf8_lossy(&self.flushed.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for FlushOnlyBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        self.flushed.lock().unwrap().append(&mut pending);
+        Ok(())
+    }
+}
+
+/// `shutdown()` is a no-op before init, flushes every output after init, and
+/// can be called repeatedly.
+#[test]
+fn test_shutdown_flushes_outputs() {
+    nanologger::shutdown();
+
+    let buf = FlushOnlyBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Info, "last words", "test_mod", "test.rs", 1);
+    assert!(buf_reader.flushed().is_empty());
+
+    nanologger::shutdown();
+    assert_eq!(buf_reader.flushed(), "[INFO]  last words\n");
+
+    nanologger::shutdown();
+    assert_eq!(buf_reader.flushed(), "[INFO]  last words\n");
+}
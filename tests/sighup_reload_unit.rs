@@ -0,0 +1,67 @@
+#![cfg(unix)]
+
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+extern "C" {
+    fn raise(sig: i32) -> i32;
+}
+
+fn log_from(module: &str, message: &str) {
+    nanologger::__log_with_context(LogLevel::Info, message, module, "reload.rs", 1);
+}
+
+/// SIGHUP swaps in the filters from NANOLOG_MODULES on the next log call, and
+/// falls back to the builder lists once the variable is removed.
+#[test]
+fn test_sighup_reloads_module_filters() {
+    std::env::set_var("NANOLOG_MODULES", "app,-app::noisy");
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .module_deny(vec!["app".to_string()])
+        .reload_on_sighup(true)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    // The variable is applied at init.
+    log_from("app::db", "one");
+    log_from("app::noisy", "two");
+    log_from("other", "three");
+
+    std::env::set_var("NANOLOG_MODULES", "other");
+    // SAFETY: the handler installed by init only sets a flag.
+    assert_eq!(unsafe { raise(1) }, 0);
+    log_from("app::db", "four");
+    log_from("other", "five");
+
+    std::env::remove_var("NANOLOG_MODULES");
+    assert_eq!(unsafe { raise(1) }, 0);
+    log_from("app::db", "six");
+    log_from("other", "seven");
+
+    assert_eq!(buf.contents(), "[INFO]  one\n[INFO]  five\n[INFO]  seven\n");
+}
@@ -0,0 +1,49 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, SourceLocationFormat};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that a custom source location format replaces `[file:line]`.
+#[test]
+fn test_source_location_format_parens_basename() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .source_location(true)
+        .source_location_format(
+            SourceLocationFormat::new()
+                .delimiters("(", ")")
+                .basename(true),
+        )
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    let line = line!() + 1;
+    nanologger::info!("located");
+    assert_eq!(
+        buf_reader.contents(),
+        format!("[INFO]  (source_location_format_unit.rs:{line}) located\n")
+    );
+}
@@ -0,0 +1,52 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Static fields appear only on the output they were attached to, after the
+/// record's own fields.
+#[test]
+fn test_static_fields_only_on_their_output() {
+    let file = SharedBuf::new();
+    let term = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(
+            LogOutput::writer(LogLevel::Trace, file.clone())
+                .with_field("env", "prod")
+                .with_field("region", "us east"),
+        )
+        .add_output(LogOutput::writer(LogLevel::Trace, term.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("started");
+    nanologger::log_kv!(LogLevel::Warn, "slow"; ms = 250);
+
+    assert_eq!(
+        file.contents(),
+        "[INFO]  started env=prod region=\"us east\"\n\
+         [WARN]  slow ms=250 env=prod region=\"us east\"\n"
+    );
+    assert_eq!(term.contents(), "[INFO]  started\n[WARN]  slow ms=250\n");
+}
@@ -0,0 +1,73 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+/// When stderr's reader goes away, lines go to the term output's fallback and
+/// stderr is abandoned after a few failures, with a one-time note.
+///
+/// Runs itself as a subprocess (with __NANOLOG_STDERR_CLOSED=1) whose stderr
+/// pipe the parent closes; the fallback writes to stdout.
+#[test]
+fn test_term_falls_back_when_stderr_breaks() {
+    if std::env::var("__NANOLOG_STDERR_CLOSED").is_ok() {
+        // Wait until the parent has closed the pipe.
+        for _ in 0..500 {
+            if writeln!(std::io::stderr(), "probe").is_err() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        LoggerBuilder::new()
+            .level(LogLevel::Trace)
+            .add_output(
+                LogOutput::term(LogLevel::Trace)
+                    .fallback(LogOutput::writer(LogLevel::Error, std::io::stdout())),
+            )
+            .init()
+            .unwrap();
+        for i in 1..=5 {
+            nanologger::info!("line {}", i);
+        }
+        nanologger::shutdown();
+        return;
+    }
+
+    let exe = std::env::current_exe().expect("current_exe");
+    let mut child = Command::new(exe)
+        .arg("test_term_falls_back_when_stderr_breaks")
+        .arg("--exact")
+        .arg("--nocapture")
+        .env("__NANOLOG_STDERR_CLOSED", "1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn subprocess");
+    drop(child.stderr.take());
+    let mut stdout = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut stdout)
+        .unwrap();
+    assert!(child.wait().unwrap().success(), "child failed: {stdout}");
+
+    // libtest prints `test <name> ... ` right before the child's first line.
+    let start = stdout.find("[INFO]").unwrap_or(stdout.len());
+    let lines: Vec<&str> = stdout[start..]
+        .lines()
+        .filter(|l| l.starts_with("[INFO]") || l.starts_with("nanologger:"))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "[INFO]  line 1",
+            "[INFO]  line 2",
+            "nanologger: stderr failed 3 writes in a row; logging here from now on",
+            "[INFO]  line 3",
+            "[INFO]  line 4",
+            "[INFO]  line 5",
+        ],
+        "child stdout: {stdout:?}"
+    );
+}
@@ -0,0 +1,66 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Taken records are returned in order, including ones the final config
+/// filters out, and are not replayed on init.
+#[test]
+fn test_take_preinit_buffer_returns_without_replay() {
+    assert!(
+        nanologger::take_preinit_buffer().is_empty(),
+        "buffering is off"
+    );
+
+    nanologger::buffer_early_logs(8);
+    nanologger::debug!("probing config");
+    nanologger::warn!("config missing");
+    let records = nanologger::take_preinit_buffer();
+    let summary: Vec<(LogLevel, &str)> = records
+        .iter()
+        .map(|r| (r.level, r.message.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (LogLevel::Debug, "probing config"),
+            (LogLevel::Warn, "config missing")
+        ]
+    );
+    assert_eq!(records[0].module_path, module_path!());
+    assert!(records[0].file.ends_with("take_preinit_buffer_unit.rs"));
+    assert!(
+        nanologger::take_preinit_buffer().is_empty(),
+        "already taken"
+    );
+
+    nanologger::info!("still buffered");
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+    assert_eq!(buf.contents(), "[INFO]  still buffered\n");
+    assert!(nanologger::take_preinit_buffer().is_empty());
+}
@@ -0,0 +1,64 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A writer that always fails on write.
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::BrokenPipe,
+            "simulated failure",
+        ))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A tee output writes each line to every writer under one level filter,
+/// and a failing writer does not stop the others.
+#[test]
+fn test_tee_writes_to_all_writers() {
+    let a = SharedBuf::new();
+    let b = SharedBuf::new();
+    let a_reader = a.clone();
+    let b_reader = b.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::tee(
+            LogLevel::Info,
+            vec![Box::new(a), Box::new(FailingWriter), Box::new(b)],
+        ))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Warn, "teed", "test_mod", "test.rs", 1);
+    nanologger::__log_with_context(LogLevel::Debug, "filtered", "test_mod", "test.rs", 2);
+
+    assert_eq!(a_reader.contents(), "[WARN]  teed\n");
+    assert_eq!(b_reader.contents(), "[WARN]  teed\n");
+}
@@ -0,0 +1,49 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that a Term output with a custom writer keeps its colors and does
+/// not count as a second stderr output.
+#[test]
+fn test_term_writer_replaces_stderr() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+    nanocolor::set_colors_override(true);
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::term(LogLevel::Error))
+        .add_output(LogOutput::term(LogLevel::Trace).term_writer(Box::new(buf)))
+        .init()
+        .expect("a term_writer output is not a duplicate stderr output");
+
+    nanologger::warn!("into the widget");
+    let output = buf_reader.contents();
+    assert!(output.contains("into the widget"), "got: {output:?}");
+    assert!(
+        output.contains("\x1b["),
+        "expected ANSI codes, got: {output:?}"
+    );
+    assert_eq!(nanologger::output_bytes(1), Some(output.len() as u64));
+}
@@ -19,4 +19,35 @@ fn test_logger_captured_with_thread_info() {
     // If this test passes, the harness captured it (no panic from print!).
     // When the test fails, you'd see the log output — that's the whole point.
     nanologger::info!("test logger capture works");
+
+    #[cfg(feature = "test-util")]
+    assert_captured_content();
+}
+
+/// Checks that `capture_test_output` returns exactly what the Test sink
+/// wrote, that nested captures each get their own lines, and that other
+/// threads' lines are left alone.
+#[cfg(feature = "test-util")]
+fn assert_captured_content() {
+    let out = nanologger::capture_test_output(|| nanologger::info!("captured"));
+    let thread = std::thread::current().name().unwrap().to_string();
+    assert_eq!(out, format!("({thread}) [INFO]  captured\n"));
+
+    let outer = nanologger::capture_test_output(|| {
+        nanologger::warn!("first");
+        let inner = nanologger::capture_test_output(|| nanologger::debug!("nested"));
+        assert!(inner.ends_with("[DEBUG] nested\n"), "got: {inner:?}");
+        nanologger::error!("last");
+    });
+    let lines: Vec<&str> = outer.lines().collect();
+    assert_eq!(lines.len(), 2, "got: {outer:?}");
+    assert!(lines[0].ends_with("[WARN]  first"), "got: {outer:?}");
+    assert!(lines[1].ends_with("[ERROR] last"), "got: {outer:?}");
+
+    let captured = nanologger::capture_test_output(|| {
+        std::thread::spawn(|| nanologger::info!("elsewhere"))
+            .join()
+            .unwrap();
+    });
+    assert_eq!(captured, "");
 }
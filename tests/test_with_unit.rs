@@ -0,0 +1,22 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::sync::{Arc, Mutex};
+
+/// `LogOutput::test_with` passes each formatted line to the provided closure.
+#[test]
+fn test_test_output_with_custom_sink() {
+    let captured = Arc::new(Mutex::new(Vec::<String>::new()));
+    let sink = Arc::clone(&captured);
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::test_with(LogLevel::Info, move |line| {
+            sink.lock().unwrap().push(line.to_string())
+        }))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Warn, "captured", "test_mod", "test.rs", 1);
+    nanologger::__log_with_context(LogLevel::Debug, "filtered", "test_mod", "test.rs", 2);
+
+    assert_eq!(*captured.lock().unwrap(), vec!["[WARN]  captured\n"]);
+}
@@ -0,0 +1,61 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Threads spawned via `thread_builder` start with the configured level
+/// override and name; without `.level()` they use the global level.
+#[test]
+fn test_thread_builder_sets_level_for_spawned_thread() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::thread_builder()
+        .name("worker-1")
+        .level(LogLevel::Debug)
+        .spawn(|| {
+            assert_eq!(std::thread::current().name(), Some("worker-1"));
+            assert_eq!(nanologger::thread_level(), Some(LogLevel::Debug));
+            nanologger::debug!("worker debug");
+            nanologger::trace!("worker trace");
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    nanologger::thread_builder()
+        .spawn(|| {
+            assert_eq!(nanologger::thread_level(), None);
+            nanologger::debug!("plain debug");
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(nanologger::thread_level(), None);
+    assert_eq!(buf.contents(), "[DEBUG] worker debug\n");
+}
@@ -0,0 +1,41 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, ThreadIdStyle};
+use std::sync::{Arc, Mutex};
+
+/// Numeric style renders unnamed threads as a plain integer that stays the
+/// same for every record from that thread; named threads keep their name.
+#[test]
+fn test_numeric_thread_ids() {
+    let lines = Arc::new(Mutex::new(Vec::<String>::new()));
+    let sink = lines.clone();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .thread_info(true)
+        .thread_id_style(ThreadIdStyle::Numeric)
+        .add_output(LogOutput::test_with(LogLevel::Trace, move |line| {
+            sink.lock().unwrap().push(line.to_string());
+        }))
+        .init()
+        .expect("init should succeed");
+
+    let worker = || {
+        nanologger::info!("first");
+        nanologger::info!("second");
+    };
+    std::thread::spawn(worker).join().unwrap();
+    std::thread::spawn(worker).join().unwrap();
+    std::thread::Builder::new()
+        .name("named".into())
+        .spawn(|| nanologger::info!("third"))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let lines = lines.lock().unwrap();
+    let id = |line: &str| -> String { line[1..line.find(')').unwrap()].to_string() };
+    let first: u64 = id(&lines[0]).parse().expect("numeric id");
+    assert_eq!(id(&lines[1]), first.to_string());
+    let second: u64 = id(&lines[2]).parse().expect("numeric id");
+    assert_ne!(first, second);
+    assert_eq!(id(&lines[3]), second.to_string());
+    assert_eq!(lines[4], "(named) [INFO]  third\n");
+}
@@ -0,0 +1,59 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A thread-level override only affects the thread that set it, can be more
+/// or less verbose than the global level, and `None` restores the global level.
+#[test]
+fn test_thread_level_override_is_thread_local() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    std::thread::spawn(|| {
+        nanologger::set_thread_level(Some(LogLevel::Trace));
+        assert_eq!(nanologger::thread_level(), Some(LogLevel::Trace));
+        nanologger::__log_with_context(LogLevel::Trace, "worker trace", "test_mod", "test.rs", 1);
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(nanologger::thread_level(), None);
+    nanologger::__log_with_context(LogLevel::Trace, "main trace", "test_mod", "test.rs", 2);
+
+    nanologger::set_thread_level(Some(LogLevel::Error));
+    nanologger::__log_with_context(LogLevel::Info, "main info muted", "test_mod", "test.rs", 3);
+    nanologger::set_thread_level(None);
+    nanologger::__log_with_context(LogLevel::Info, "main info", "test_mod", "test.rs", 4);
+
+    assert_eq!(
+        buf_reader.contents(),
+        "[TRACE] worker trace\n[INFO]  main info\n"
+    );
+}
@@ -0,0 +1,67 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder, ThrottleKey};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that interleaved repeats are suppressed within the window and
+/// summarized once it has passed.
+#[test]
+fn test_throttle_suppresses_interleaved_repeats() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .throttle(Duration::from_millis(200), ThrottleKey::MessageAndModule)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    let log = |message: &str, module: &str| {
+        nanologger::__log_with_context(LogLevel::Warn, message, module, "x.rs", 1);
+    };
+    log("disk almost full", "app::disk");
+    log("request served", "app::web");
+    log("disk almost full", "app::disk");
+    log("disk almost full", "app::other");
+    log("disk almost full", "app::disk");
+    assert_eq!(
+        buf_reader.contents(),
+        "[WARN]  disk almost full\n\
+         [WARN]  request served\n\
+         [WARN]  disk almost full\n",
+        "different modules are throttled separately"
+    );
+
+    std::thread::sleep(Duration::from_millis(250));
+    log("disk almost full", "app::disk");
+    let output = buf_reader.contents();
+    assert!(
+        output.ends_with(
+            "[WARN]  suppressed 2 repeats of \"disk almost full\"\n\
+             [WARN]  disk almost full\n"
+        ),
+        "got: {output:?}"
+    );
+    assert_eq!(nanologger::stats().unwrap().total(), 4);
+}
@@ -0,0 +1,65 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Test that a timed guard logs a start line and a completion line with the
+/// elapsed time, at the caller's location.
+#[test]
+fn test_timed_logs_start_and_completion() {
+    let buf = SharedBuf::new();
+    let buf_reader = buf.clone();
+
+    LoggerBuilder::new()
+        .level(LogLevel::Debug)
+        .source_location(true)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf))
+        .init()
+        .expect("init should succeed");
+
+    let line = line!() + 2;
+    {
+        let _timer = nanologger::timed(LogLevel::Debug, "load config");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert_eq!(
+            buf_reader.contents(),
+            format!("[DEBUG] [tests/timed_unit.rs:{line}] load config: started\n")
+        );
+    }
+    let output = buf_reader.contents();
+    let done = output.lines().nth(1).expect("completion line");
+    let prefix = format!("[DEBUG] [tests/timed_unit.rs:{line}] load config: completed in ");
+    let elapsed = done
+        .strip_prefix(&prefix)
+        .unwrap_or_else(|| panic!("got: {done:?}"));
+    let ms: f64 = elapsed
+        .strip_suffix("ms")
+        .expect("milliseconds")
+        .parse()
+        .unwrap();
+    assert!(ms >= 20.0, "got: {elapsed}");
+
+    // Filtered out by the global level: nothing is logged.
+    drop(nanologger::timed(LogLevel::Trace, "hidden"));
+    assert_eq!(buf_reader.contents().lines().count(), 2);
+}
@@ -0,0 +1,54 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Only records at or above the threshold get a timestamp; explicit
+/// timestamps are always shown.
+#[test]
+fn test_timestamp_from_threshold() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .timestamps(true)
+        .timestamp_from(LogLevel::Warn)
+        .clock(|| "12:00:00.000".to_string())
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::error!("boom");
+    nanologger::warn!("careful");
+    nanologger::info!("fine");
+    nanologger::debug!("details");
+    nanologger::log_at(LogLevel::Info, "replayed", "09:00:00.000");
+
+    assert_eq!(
+        buf.contents(),
+        "12:00:00.000 [ERROR] boom\n\
+         12:00:00.000 [WARN]  careful\n\
+         [INFO]  fine\n\
+         [DEBUG] details\n\
+         09:00:00.000 [INFO]  replayed\n"
+    );
+}
@@ -0,0 +1,63 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn utc_plus(minutes: i64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    let ms = (now + minutes * 60_000).rem_euclid(86_400_000);
+    format!(
+        "{:02}:{:02}:{:02}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60
+    )
+}
+
+/// A fixed offset shifts timestamps from UTC independent of the host zone.
+#[test]
+fn test_timezone_offset_shifts_utc() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .timestamps(true)
+        .timezone_offset(-150)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    let before = utc_plus(-150);
+    nanologger::info!("hello");
+    let after = utc_plus(-150);
+
+    let out = buf.contents();
+    let stamp = &out[..8];
+    assert!(
+        stamp == before || stamp == after,
+        "got {out:?}, expected {before} or {after}"
+    );
+    assert!(out.ends_with(" [INFO]  hello\n"), "got {out:?}");
+}
@@ -0,0 +1,26 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::sync::{Arc, Mutex};
+
+/// With `trailing_newline(false)`, records reach the test sink without a terminator.
+#[test]
+fn test_trailing_newline_disabled() {
+    let captured = Arc::new(Mutex::new(Vec::<String>::new()));
+    let sink = Arc::clone(&captured);
+
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .trailing_newline(false)
+        .add_output(LogOutput::test_with(LogLevel::Trace, move |line| {
+            sink.lock().unwrap().push(line.to_string())
+        }))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::__log_with_context(LogLevel::Info, "frame one", "test_mod", "test.rs", 1);
+    nanologger::__log_with_context(LogLevel::Info, "frame two", "test_mod", "test.rs", 2);
+
+    assert_eq!(
+        *captured.lock().unwrap(),
+        vec!["[INFO]  frame one", "[INFO]  frame two"]
+    );
+}
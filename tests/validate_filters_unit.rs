@@ -0,0 +1,55 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `init` warns once per malformed entry, even though the allow list filters
+/// out nanologger's own module. Targets with `-` or `.` are not flagged.
+#[test]
+fn test_validate_filters_warns_on_init() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Info)
+        .validate_filters(true)
+        .module_allow(vec![
+            "myapp::".to_string(),
+            "validate_filters_unit".to_string(),
+        ])
+        .module_deny(vec![
+            " myapp::db".to_string(),
+            "my-crate".to_string(),
+            "hyper.client".to_string(),
+        ])
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("after init");
+
+    assert_eq!(
+        buf.contents(),
+        "[WARN]  suspicious module allow entry \"myapp::\": it has an empty `::` segment\n\
+         [WARN]  suspicious module deny entry \" myapp::db\": it has surrounding whitespace\n\
+         [INFO]  after init\n"
+    );
+}
@@ -0,0 +1,53 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// `%message` logs strings verbatim, braces included, through every macro.
+#[test]
+fn test_verbatim_messages() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .level(LogLevel::Trace)
+        .add_output(LogOutput::writer(LogLevel::Trace, buf.clone()))
+        .init()
+        .expect("init should succeed");
+
+    let owned = String::from("owned {0} {name}");
+    nanologger::error!(%owned);
+    nanologger::warn!(%"literal {}");
+    nanologger::info!(%&owned);
+    nanologger::debug!(%owned.as_str());
+    nanologger::trace!(%format!("{}{{", 1));
+    nanologger::log!(LogLevel::Info, %owned);
+
+    assert_eq!(
+        buf.contents(),
+        "[ERROR] owned {0} {name}\n\
+         [WARN]  literal {}\n\
+         [INFO]  owned {0} {name}\n\
+         [DEBUG] owned {0} {name}\n\
+         [TRACE] 1{\n\
+         [INFO]  owned {0} {name}\n"
+    );
+}
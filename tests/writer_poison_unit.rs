@@ -0,0 +1,60 @@
+use nanologger::{LogLevel, LogOutput, LoggerBuilder};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuf {
+    fn new() -> Self {
+        SharedBuf(Arc::new(Mutex::new(Vec::new())))
+    }
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+    }
+}
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Panics on any write containing `boom`, while the output's lock is held.
+struct PanickyWriter(SharedBuf);
+
+impl Write for PanickyWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if String::from_utf8_lossy(buf).contains("boom") {
+            panic!("writer panicked mid-write");
+        }
+        self.0.write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A panic while a writer's lock is held poisons it; later records must
+/// still reach the writer.
+#[test]
+fn test_writer_survives_poisoned_lock() {
+    let buf = SharedBuf::new();
+    LoggerBuilder::new()
+        .add_output(LogOutput::writer(
+            LogLevel::Trace,
+            PanickyWriter(buf.clone()),
+        ))
+        .init()
+        .expect("init should succeed");
+
+    nanologger::info!("before");
+    let result = std::thread::spawn(|| nanologger::info!("boom")).join();
+    assert!(result.is_err(), "the write should have panicked");
+    nanologger::info!("after");
+
+    assert_eq!(buf.contents(), "[INFO]  before\n[INFO]  after\n");
+}